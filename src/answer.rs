@@ -0,0 +1,114 @@
+use std::fmt::Display;
+
+use num::{BigInt, BigUint, ToPrimitive};
+
+/// The answer to a part of a puzzle
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Answer {
+    Integer(i64),
+    /// An integer that does not fit in an `i64`, smaller ones are always stored as `Integer`
+    BigInteger(BigInt),
+    /// A single line of text, like a list of labels
+    String(String),
+    /// Several lines of text, like a rendered image
+    Text(String),
+}
+
+impl Answer {
+    /// Whether both answers are written the same way, regardless of their type,
+    /// as expected answers written by hand cannot tell labels from numbers
+    pub fn matches(&self, other: &Answer) -> bool {
+        self.to_string() == other.to_string()
+    }
+
+    pub fn is_multiline(&self) -> bool {
+        matches!(self, Answer::Text(_))
+    }
+}
+
+impl Display for Answer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Answer::Integer(value) => write!(f, "{}", value),
+            Answer::BigInteger(value) => write!(f, "{}", value),
+            Answer::String(value) => write!(f, "{}", value),
+            Answer::Text(value) => write!(f, "{}", value.trim_end()),
+        }
+    }
+}
+
+macro_rules! impl_from_integer {
+    ($($t:ty),*) => {
+        $(
+            impl From<$t> for Answer {
+                fn from(value: $t) -> Self {
+                    match i64::try_from(value) {
+                        Ok(value) => Answer::Integer(value),
+                        Err(_) => Answer::BigInteger(BigInt::from(value)),
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_from_integer!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize);
+
+impl From<BigInt> for Answer {
+    fn from(value: BigInt) -> Self {
+        match value.to_i64() {
+            Some(value) => Answer::Integer(value),
+            None => Answer::BigInteger(value),
+        }
+    }
+}
+
+impl From<BigUint> for Answer {
+    fn from(value: BigUint) -> Self {
+        Answer::from(BigInt::from(value))
+    }
+}
+
+/// Multi-line strings become `Text`, the others `String`
+impl From<String> for Answer {
+    fn from(value: String) -> Self {
+        if value.trim_end().contains('\n') {
+            Answer::Text(value)
+        } else {
+            Answer::String(value)
+        }
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(value: &str) -> Self {
+        Answer::from(value.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn integers_are_normalised() {
+        assert_eq!(Answer::from(42usize), Answer::Integer(42));
+        assert_eq!(Answer::from(BigUint::from(42u32)), Answer::Integer(42));
+        assert_eq!(Answer::from(u64::MAX), Answer::BigInteger(BigInt::from(u64::MAX)));
+        assert_eq!(Answer::from(u64::MAX).to_string(), "18446744073709551615");
+    }
+
+    #[test]
+    fn strings() {
+        assert_eq!(Answer::from("67384529"), Answer::String("67384529".to_string()));
+        assert!(Answer::from("#.#\n.#.\n").is_multiline());
+        assert_eq!(Answer::from("#.#\n.#.\n").to_string(), "#.#\n.#.");
+    }
+
+    #[test]
+    fn matches() {
+        assert!(Answer::from(2516).matches(&Answer::from("2516")));
+        assert!(Answer::from("67384529").matches(&Answer::from(67384529)));
+        assert!(!Answer::from("0123").matches(&Answer::from(123)));
+    }
+}
@@ -0,0 +1,131 @@
+use std::collections::HashMap;
+use std::fs::read_to_string;
+use std::path::{Path, PathBuf};
+
+use crate::answer::Answer;
+use crate::error::{Error, Result};
+use crate::runner::DayResult;
+use crate::solver::Part;
+
+/// Outcome of the comparison of an answer with the expected one
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Check {
+    Pass,
+    Fail { expected: Answer },
+    /// No expected answer is known for this part
+    Missing,
+}
+
+/// Known answers, per day and part, as stored in `answers.toml`:
+///
+/// ```toml
+/// [day10]
+/// part1 = "2516"
+/// part2 = "296196766695424"
+/// ```
+pub struct Answers {
+    answers: HashMap<(u8, Part), Answer>,
+}
+
+/// The default answers file, at the crate root
+pub fn default_path() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("answers.toml")
+}
+
+impl Answers {
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = read_to_string(path).map_err(|e| Error::new(format!("{}: {}", path.display(), e)))?;
+        Self::parse(&content).map_err(|e| Error::new(format!("{}: {}", path.display(), e.message)))
+    }
+
+    pub fn parse(content: &str) -> Result<Self> {
+        let table: toml::Table = content.parse().map_err(|e: toml::de::Error| Error::new(e.message()))?;
+
+        let mut answers = HashMap::new();
+        for (key, value) in &table {
+            let day = key.strip_prefix("day")
+                .and_then(|day| day.parse::<u8>().ok())
+                .ok_or_else(|| Error::new(format!("invalid section `{}`, expected `dayNN`", key)))?;
+            let parts = value.as_table()
+                .ok_or_else(|| Error::new(format!("`{}` should be a table", key)))?;
+
+            for (part_key, answer) in parts {
+                let part = match part_key.as_str() {
+                    "part1" => Part::One,
+                    "part2" => Part::Two,
+                    _ => return Err(Error::new(format!("invalid key `{}.{}`, expected `part1` or `part2`", key, part_key))),
+                };
+                let answer = match answer {
+                    toml::Value::String(answer) => Answer::from(answer.as_str()),
+                    toml::Value::Integer(answer) => Answer::from(*answer),
+                    _ => return Err(Error::new(format!("`{}.{}` should be a string or an integer", key, part_key))),
+                };
+                answers.insert((day, part), answer);
+            }
+        }
+
+        Ok(Self { answers })
+    }
+
+    pub fn expected(&self, day: u8, part: Part) -> Option<&Answer> {
+        self.answers.get(&(day, part))
+    }
+
+    /// Compares every answer of the result to the expected ones
+    pub fn check(&self, result: &mut DayResult) {
+        for part in result.parts.iter_mut() {
+            part.check = Some(match self.expected(result.day, part.part) {
+                Some(expected) if expected.matches(&part.answer) => Check::Pass,
+                Some(expected) => Check::Fail { expected: expected.clone() },
+                None => Check::Missing,
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::runner::PartResult;
+
+    #[test]
+    fn parse() {
+        let answers = Answers::parse("[day10]\npart1 = \"2516\"\npart2 = 296196766695424\n\n[day20]\npart2 = \"#.\\n.#\\n\"\n").unwrap();
+        assert_eq!(answers.expected(10, Part::One), Some(&Answer::from("2516")));
+        assert_eq!(answers.expected(10, Part::Two), Some(&Answer::from(296196766695424i64)));
+        assert_eq!(answers.expected(20, Part::Two), Some(&Answer::from("#.\n.#\n")));
+        assert_eq!(answers.expected(20, Part::One), None);
+        assert_eq!(answers.expected(11, Part::One), None);
+    }
+
+    #[test]
+    fn invalid_answers() {
+        let error = Answers::parse("[week10]\npart1 = 1\n").err().unwrap();
+        assert_eq!(error.message, "invalid section `week10`, expected `dayNN`");
+        let error = Answers::parse("[day10]\npart3 = 1\n").err().unwrap();
+        assert_eq!(error.message, "invalid key `day10.part3`, expected `part1` or `part2`");
+        let error = Answers::parse("[day10]\npart1 = 2.5\n").err().unwrap();
+        assert_eq!(error.message, "`day10.part1` should be a string or an integer");
+        assert!(Answers::parse("day10 = 1\n").is_err());
+    }
+
+    #[test]
+    fn check() {
+        let answers = Answers::parse("[day10]\npart1 = 2516\n\n[day11]\npart1 = 2\npart2 = 3\n").unwrap();
+        let part = |part, answer: i32| PartResult { part, answer: Answer::from(answer), duration: Duration::ZERO, check: None };
+
+        let mut result = DayResult { day: 10, parse_duration: Duration::ZERO, parts: vec![part(Part::One, 2516), part(Part::Two, 7)] };
+        answers.check(&mut result);
+        assert_eq!(result.parts[0].check, Some(Check::Pass));
+        assert_eq!(result.parts[1].check, Some(Check::Missing));
+        assert!(!result.has_failed_check());
+
+        let mut result = DayResult { day: 11, parse_duration: Duration::ZERO, parts: vec![part(Part::One, 2), part(Part::Two, 4)] };
+        answers.check(&mut result);
+        assert_eq!(result.parts[0].check, Some(Check::Pass));
+        assert_eq!(result.parts[1].check, Some(Check::Fail { expected: Answer::from(3) }));
+        assert!(result.has_failed_check());
+    }
+}
@@ -0,0 +1,577 @@
+use std::fmt::Display;
+use std::ops::Range;
+
+/// A rectangular grid of cells, stored row by row
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Grid<C> {
+    width: usize,
+    height: usize,
+    cells: Vec<C>,
+}
+
+impl<C> Grid<C> {
+    pub fn new(width: usize, height: usize, cells: Vec<C>) -> Self {
+        assert_eq!(cells.len(), width * height, "expected {}x{} cells", width, height);
+        Self { width, height, cells }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn cells(&self) -> &[C] {
+        &self.cells
+    }
+
+    pub fn index(&self, x: usize, y: usize) -> usize {
+        y * self.width + x
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> &C {
+        &self.cells[self.index(x, y)]
+    }
+
+    /// Number of cells matching the predicate
+    pub fn count(&self, predicate: impl Fn(&C) -> bool) -> usize {
+        self.cells.iter().filter(|cell| predicate(cell)).count()
+    }
+
+    /// One line of characters per row
+    pub fn render(&self, as_char: impl Fn(&C) -> char) -> String {
+        let mut rendered = String::with_capacity((self.width + 1) * self.height);
+        for row in self.cells.chunks(self.width) {
+            rendered.extend(row.iter().map(&as_char));
+            rendered.push('\n');
+        }
+        rendered
+    }
+
+    /// Moves one cell from `position` in `direction`, returning the new position and direction,
+    /// the direction changing when reflected by an edge
+    fn advance(&self, (x, y): (usize, usize), (dx, dy): (isize, isize), boundary: Boundary) -> Option<((usize, usize), (isize, isize))> {
+        let (x, dx) = boundary.advance(x, dx, self.width)?;
+        let (y, dy) = boundary.advance(y, dy, self.height)?;
+        Some(((x, y), (dx, dy)))
+    }
+}
+
+/// What lies beyond the edges of the grid
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Boundary {
+    /// Nothing, the cells on the edges have fewer neighbours
+    #[default]
+    Bounded,
+    /// The opposite edge, as if the grid was wrapped around a torus
+    Toroidal,
+    /// A mirror image of the grid, as if a mirror stood along each edge
+    Reflective,
+}
+
+impl TryFrom<&str> for Boundary {
+    type Error = ();
+
+    fn try_from(value: &str) -> std::result::Result<Self, Self::Error> {
+        match value {
+            "bounded" => Ok(Boundary::Bounded),
+            "toroidal" => Ok(Boundary::Toroidal),
+            "reflective" => Ok(Boundary::Reflective),
+            _ => Err(()),
+        }
+    }
+}
+
+impl Boundary {
+    /// Moves along one axis of length `size` by `delta`, which is at most one cell
+    fn advance(&self, value: usize, delta: isize, size: usize) -> Option<(usize, isize)> {
+        if let Some(moved) = value.checked_add_signed(delta).filter(|&moved| moved < size) {
+            return Some((moved, delta));
+        }
+
+        match self {
+            Boundary::Bounded => None,
+            Boundary::Toroidal => Some(((value as isize + delta).rem_euclid(size as isize) as usize, delta)),
+            // The mirror image of a cell on the edge is the cell itself, seen going the other way
+            Boundary::Reflective => Some((value, -delta)),
+        }
+    }
+}
+
+const ORTHOGONAL: [(isize, isize); 4] = [(0, -1), (-1, 0), (1, 0), (0, 1)];
+const ALL_DIRECTIONS: [(isize, isize); 8] = [(-1, -1), (0, -1), (1, -1), (-1, 0), (1, 0), (-1, 1), (0, 1), (1, 1)];
+
+/// Which cells are the neighbours of a cell
+#[derive(Clone, Copy)]
+pub enum Neighbourhood<C> {
+    /// The 8 surrounding cells
+    Moore,
+    /// The 4 orthogonally adjacent cells
+    VonNeumann,
+    /// The first cell seen in each of the 8 directions, looking through the transparent ones
+    LineOfSight { transparent: fn(&C) -> bool },
+}
+
+impl<C> Neighbourhood<C> {
+    /// Indices of the neighbours of the cell at `(x, y)`, a cell being possibly its own neighbour
+    /// or the same neighbour twice with a boundary other than `Bounded`
+    pub fn neighbours(&self, grid: &Grid<C>, boundary: Boundary, x: usize, y: usize) -> Vec<usize> {
+        let mut neighbours = Vec::with_capacity(8);
+        self.for_each_neighbour(grid, boundary, x, y, |neighbour| neighbours.push(neighbour));
+        neighbours
+    }
+
+    /// Calls `f` with the index of every neighbour of the cell at `(x, y)`, in the order of `neighbours`
+    fn for_each_neighbour(&self, grid: &Grid<C>, boundary: Boundary, x: usize, y: usize, mut f: impl FnMut(usize)) {
+        let directions: &[(isize, isize)] = match self {
+            Neighbourhood::VonNeumann => &ORTHOGONAL,
+            _ => &ALL_DIRECTIONS,
+        };
+
+        for &direction in directions {
+            let neighbour = match self {
+                Neighbourhood::Moore | Neighbourhood::VonNeumann => grid.advance((x, y), direction, boundary).map(|(position, _)| position),
+                Neighbourhood::LineOfSight { transparent } => Self::line_of_sight(grid, boundary, (x, y), direction, *transparent),
+            };
+            if let Some((x, y)) = neighbour {
+                f(grid.index(x, y));
+            }
+        }
+    }
+
+    /// The first opaque cell seen from `position` in `direction`, if any
+    fn line_of_sight(grid: &Grid<C>, boundary: Boundary, position: (usize, usize), direction: (isize, isize), transparent: fn(&C) -> bool) -> Option<(usize, usize)> {
+        let start = (position, direction);
+        let (mut position, mut direction) = grid.advance(position, direction, boundary)?;
+        while transparent(grid.get(position.0, position.1)) {
+            // Without edges, the line of sight ends up back where it started
+            if (position, direction) == start {
+                return None;
+            }
+            (position, direction) = grid.advance(position, direction, boundary)?;
+        }
+        Some(position)
+    }
+}
+
+/// Gives the next state of a cell from its current state and the number of its neighbours that are counted
+pub trait Rule<C>: Fn(&C, usize) -> C + Sync {}
+
+impl<C, F: Fn(&C, usize) -> C + Sync> Rule<C> for F {}
+
+/// A grid whose cells all evolve at once, the next state of each cell being given by the rule
+/// from its current state and the number of its neighbours for which `counted` holds.
+///
+/// The neighbours of every cell are computed once, from the initial grid: a line of sight
+/// neighbourhood assumes that cells never become transparent or opaque. The counts are then
+/// updated around the cells that change, rather than looking at every neighbour at each step.
+pub struct Automaton<C, R> {
+    grid: Grid<C>,
+    /// The cells having the i-th cell as a neighbour, as many times as they have it,
+    /// are `watchers[watcher_starts[i]..watcher_starts[i + 1]]`
+    watcher_starts: Vec<usize>,
+    watchers: Vec<u32>,
+    /// Number of counted neighbours of each cell, a cell having at most 8 neighbours
+    counts: Vec<u8>,
+    /// Bit `k` of the mask of a cell is set when the cell changes with `k` counted neighbours,
+    /// so that the cells that never change, such as the floor of the seating, are skipped cheaply
+    masks: Vec<u16>,
+    /// The mask of the states already seen, to avoid applying the rule to every count again
+    known_masks: Vec<(C, u16)>,
+    /// The cells that changed during the last step, with their previous state
+    changed: Vec<(usize, C)>,
+    counted: fn(&C) -> bool,
+    rule: R,
+    generation: usize,
+    threads: usize,
+}
+
+impl<C, R> Automaton<C, R>
+where
+    C: Clone + PartialEq + Send + Sync,
+    R: Rule<C>,
+{
+    pub fn new(grid: Grid<C>, neighbourhood: Neighbourhood<C>, counted: fn(&C) -> bool, rule: R) -> Self {
+        Self::with_boundary(grid, neighbourhood, Boundary::Bounded, counted, rule)
+    }
+
+    pub fn with_boundary(grid: Grid<C>, neighbourhood: Neighbourhood<C>, boundary: Boundary, counted: fn(&C) -> bool, rule: R) -> Self {
+        let size = grid.cells.len();
+        assert!(size <= u32::MAX as usize, "at most 2^32 cells are supported");
+
+        let mut pairs = Vec::with_capacity(size * 8);
+        let mut counts = Vec::with_capacity(size);
+        let mut masks = Vec::with_capacity(size);
+        let mut known_masks = vec![];
+        for y in 0..grid.height {
+            for x in 0..grid.width {
+                let cell = grid.index(x, y) as u32;
+                let mut count = 0;
+                neighbourhood.for_each_neighbour(&grid, boundary, x, y, |neighbour| {
+                    pairs.push((neighbour as u32, cell));
+                    count += counted(&grid.cells[neighbour]) as u8;
+                });
+                counts.push(count);
+                masks.push(Self::mask(&rule, &mut known_masks, &grid.cells[cell as usize]));
+            }
+        }
+
+        // The watchers of a cell are the cells it is a neighbour of, grouped with a counting sort
+        let mut watcher_starts = vec![0; size + 1];
+        for &(neighbour, _) in &pairs {
+            watcher_starts[neighbour as usize + 1] += 1;
+        }
+        for i in 0..size {
+            watcher_starts[i + 1] += watcher_starts[i];
+        }
+        let mut watchers = vec![0; pairs.len()];
+        let mut ends = watcher_starts.clone();
+        for (neighbour, cell) in pairs {
+            watchers[ends[neighbour as usize]] = cell;
+            ends[neighbour as usize] += 1;
+        }
+
+        Self {
+            grid,
+            watcher_starts,
+            watchers,
+            counts,
+            masks,
+            known_masks,
+            changed: vec![],
+            counted,
+            rule,
+            generation: 0,
+            threads: 1,
+        }
+    }
+
+    pub fn grid(&self) -> &Grid<C> {
+        &self.grid
+    }
+
+    /// Number of steps done so far
+    pub fn generation(&self) -> usize {
+        self.generation
+    }
+
+    /// Splits the cells of each step over the given number of threads, which pays off on large grids
+    pub fn with_threads(mut self, threads: usize) -> Self {
+        assert!(threads >= 1, "at least one thread is needed");
+        self.threads = threads;
+        self
+    }
+
+    /// The counts of counted neighbours with which a cell in the given state changes, as a mask
+    fn mask(rule: &R, known_masks: &mut Vec<(C, u16)>, state: &C) -> u16 {
+        if let Some((_, mask)) = known_masks.iter().find(|(known, _)| known == state) {
+            return *mask;
+        }
+
+        let mask = (0..=8).filter(|&count| rule(state, count) != *state).fold(0, |mask, count| mask | 1 << count);
+        // Only a few states are remembered, looking them up must stay cheaper than applying the rule
+        if known_masks.len() < 16 {
+            known_masks.push((state.clone(), mask));
+        }
+        mask
+    }
+
+    /// Computes the next generation, returning whether any cell changed
+    pub fn step(&mut self) -> bool {
+        let automaton = &*self;
+        let size = self.grid.cells.len();
+        let changing = if self.threads == 1 {
+            automaton.changing(0..size)
+        } else {
+            let block = size.div_ceil(self.threads);
+            std::thread::scope(|scope| {
+                let handles: Vec<_> = (0..size).step_by(block)
+                    .map(|start| scope.spawn(move || automaton.changing(start..size.min(start + block))))
+                    .collect();
+                handles.into_iter().flat_map(|handle| handle.join().unwrap()).collect()
+            })
+        };
+
+        // The new states only depend on the counts, which are updated once all the cells changed
+        self.changed.clear();
+        for i in changing {
+            let i = i as usize;
+            let next = (self.rule)(&self.grid.cells[i], self.counts[i] as usize);
+            self.masks[i] = Self::mask(&self.rule, &mut self.known_masks, &next);
+            self.changed.push((i, std::mem::replace(&mut self.grid.cells[i], next)));
+        }
+        for (i, previous) in &self.changed {
+            let (was_counted, is_counted) = ((self.counted)(previous), (self.counted)(&self.grid.cells[*i]));
+            if was_counted != is_counted {
+                let delta = if is_counted { 1 } else { u8::MAX };
+                for &watcher in &self.watchers[self.watcher_starts[*i]..self.watcher_starts[*i + 1]] {
+                    self.counts[watcher as usize] = self.counts[watcher as usize].wrapping_add(delta);
+                }
+            }
+        }
+
+        self.generation += 1;
+        !self.changed.is_empty()
+    }
+
+    /// The cells of the range that change at the next step
+    fn changing(&self, cells: Range<usize>) -> Vec<u32> {
+        // Every cell is written, and kept by moving past it only when it changes, which
+        // avoids branching on changes that are hard to predict
+        let mut changing = vec![0; cells.len()];
+        let mut length = 0;
+        for (i, (&mask, &count)) in self.masks[cells.clone()].iter().zip(&self.counts[cells.clone()]).enumerate() {
+            changing[length] = (cells.start + i) as u32;
+            length += (mask >> count & 1) as usize;
+        }
+        changing.truncate(length);
+        changing
+    }
+
+    /// The cells following the given ones, counting their neighbours again
+    fn replay_step(&self, cells: &[C]) -> Vec<C> {
+        let mut counts = vec![0; cells.len()];
+        for (i, cell) in cells.iter().enumerate() {
+            if (self.counted)(cell) {
+                for &watcher in &self.watchers[self.watcher_starts[i]..self.watcher_starts[i + 1]] {
+                    counts[watcher as usize] += 1;
+                }
+            }
+        }
+        cells.iter().zip(counts).map(|(cell, count)| (self.rule)(cell, count)).collect()
+    }
+
+    /// Steps until the grid stops changing or repeats an earlier generation,
+    /// or until the generation `max_generations` is reached
+    pub fn run(&mut self, max_generations: Option<usize>) -> Outcome {
+        self.run_with(max_generations, |_| ())
+    }
+
+    /// Same as `run`, calling `on_generation` after every step that changed the grid
+    pub fn run_with(&mut self, max_generations: Option<usize>, mut on_generation: impl FnMut(&Self)) -> Outcome {
+        // Brent's algorithm: every generation is compared with the last one saved, a generation
+        // being saved whenever the distance to the previous one reaches the next power of two.
+        // The number of cells differing from the saved generation is updated from the changes.
+        let (first_generation, first_cells) = (self.generation, self.grid.cells.clone());
+        let (mut saved_generation, mut saved) = (first_generation, first_cells.clone());
+        let (mut differences, mut power) = (0, 1);
+
+        loop {
+            if max_generations.is_some_and(|max| self.generation >= max) {
+                return Outcome::Undecided { generation: self.generation };
+            }
+
+            if !self.step() {
+                return Outcome::Stable { generation: self.generation - 1 };
+            }
+            on_generation(self);
+
+            for (i, previous) in &self.changed {
+                differences += (self.grid.cells[*i] != saved[*i]) as usize;
+                differences -= (*previous != saved[*i]) as usize;
+            }
+
+            let period = self.generation - saved_generation;
+            if differences == 0 {
+                // The cycle starts at the first generation that comes back `period` generations later
+                let mut start = first_generation;
+                let mut earlier = first_cells;
+                let mut later = (0..period).fold(earlier.clone(), |cells, _| self.replay_step(&cells));
+                while earlier != later {
+                    earlier = self.replay_step(&earlier);
+                    later = self.replay_step(&later);
+                    start += 1;
+                }
+                return Outcome::Cycle { start, period };
+            }
+
+            if period == power {
+                (saved_generation, saved) = (self.generation, self.grid.cells.clone());
+                differences = 0;
+                power *= 2;
+            }
+        }
+    }
+}
+
+/// How a run of an automaton ended
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Outcome {
+    /// No cell changes after the given generation
+    Stable { generation: usize },
+    /// The generation `start` comes back every `period` generations, `period` being at least 2
+    Cycle { start: usize, period: usize },
+    /// The generation cap was reached before the grid was found stable or cyclic
+    Undecided { generation: usize },
+}
+
+impl Display for Outcome {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Outcome::Stable { generation } => write!(f, "stable after {} generations", generation),
+            Outcome::Cycle { start, period } => write!(f, "enters a cycle of period {} at generation {}", period, start),
+            Outcome::Undecided { generation } => write!(f, "neither stable nor cyclic after {} generations", generation),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn life(alive: &bool, count: usize) -> bool {
+        count == 3 || (*alive && count == 2)
+    }
+
+    fn alive(alive: &bool) -> bool {
+        *alive
+    }
+
+    fn parse(rows: &[&str]) -> Grid<char> {
+        Grid::new(rows[0].len(), rows.len(), rows.iter().flat_map(|row| row.chars()).collect())
+    }
+
+    #[test]
+    fn blinker() {
+        let vertical = Grid::new(3, 3, vec![false, true, false, false, true, false, false, true, false]);
+        let horizontal = Grid::new(3, 3, vec![false, false, false, true, true, true, false, false, false]);
+
+        let mut automaton = Automaton::new(vertical.clone(), Neighbourhood::Moore, alive, life);
+        assert!(automaton.step());
+        assert_eq!(*automaton.grid(), horizontal);
+        assert!(automaton.step());
+        assert_eq!(*automaton.grid(), vertical);
+        assert_eq!(automaton.generation(), 2);
+    }
+
+    #[test]
+    fn parallel_steps() {
+        use rand::{Rng, SeedableRng};
+
+        let mut rng = rand::rngs::StdRng::seed_from_u64(23);
+        let grid = Grid::new(37, 29, (0..37 * 29).map(|_| rng.gen_bool(0.4)).collect());
+
+        let mut serial = Automaton::with_boundary(grid.clone(), Neighbourhood::Moore, Boundary::Toroidal, alive, life);
+        let mut parallels: Vec<_> = [2, 3, 8, 29, 64].into_iter()
+            .map(|threads| Automaton::with_boundary(grid.clone(), Neighbourhood::Moore, Boundary::Toroidal, alive, life).with_threads(threads))
+            .collect();
+
+        for _ in 0..20 {
+            let changed = serial.step();
+            for parallel in parallels.iter_mut() {
+                assert_eq!(parallel.step(), changed);
+                assert_eq!(parallel.grid(), serial.grid());
+            }
+        }
+    }
+
+    /// Number of counted neighbours of every cell, counted from scratch
+    fn counts<R: Rule<char>>(automaton: &Automaton<char, R>) -> Vec<u8> {
+        let mut counts = vec![0; automaton.grid.cells.len()];
+        for (i, cell) in automaton.grid.cells.iter().enumerate() {
+            if (automaton.counted)(cell) {
+                for &watcher in &automaton.watchers[automaton.watcher_starts[i]..automaton.watcher_starts[i + 1]] {
+                    counts[watcher as usize] += 1;
+                }
+            }
+        }
+        counts
+    }
+
+    #[test]
+    fn counts_follow_the_changes() {
+        use rand::{Rng, SeedableRng};
+
+        // Reflected and wrapped lines of sight make cells their own neighbours, or the same neighbour twice
+        let mut rng = rand::rngs::StdRng::seed_from_u64(19);
+        let grid = Grid::new(13, 11, (0..13 * 11).map(|_| ['.', 'a', 'b'][rng.gen_range(0..3)]).collect());
+        let line_of_sight = Neighbourhood::LineOfSight { transparent: |c| *c == '.' };
+        let flip = |c: &char, count| match c {
+            'a' if count >= 2 => 'b',
+            'b' if count <= 1 => 'a',
+            c => *c,
+        };
+
+        for boundary in [Boundary::Bounded, Boundary::Toroidal, Boundary::Reflective] {
+            let mut automaton = Automaton::with_boundary(grid.clone(), line_of_sight, boundary, |c| *c == 'b', flip);
+            let mut cells = grid.cells().to_vec();
+            for _ in 0..10 {
+                automaton.step();
+                cells = automaton.replay_step(&cells);
+                assert_eq!(automaton.grid().cells(), cells);
+                assert_eq!(automaton.counts, counts(&automaton));
+            }
+        }
+    }
+
+    #[test]
+    fn outcomes() {
+        let blinker = Grid::new(3, 3, vec![false, true, false, false, true, false, false, true, false]);
+        let block = Grid::new(4, 4, vec![false, false, false, false, false, true, true, false, false, true, true, false, false, false, false, false]);
+        let tromino = Grid::new(4, 4, vec![false, false, false, false, false, true, true, false, false, true, false, false, false, false, false, false]);
+
+        assert_eq!(Automaton::new(blinker.clone(), Neighbourhood::Moore, alive, life).run(None), Outcome::Cycle { start: 0, period: 2 });
+        assert_eq!(Automaton::new(blinker, Neighbourhood::Moore, alive, life).run(Some(5)), Outcome::Cycle { start: 0, period: 2 });
+        assert_eq!(Automaton::new(block.clone(), Neighbourhood::Moore, alive, life).run(None), Outcome::Stable { generation: 0 });
+        assert_eq!(Automaton::new(tromino.clone(), Neighbourhood::Moore, alive, life).run(None), Outcome::Stable { generation: 1 });
+        assert_eq!(Automaton::new(tromino, Neighbourhood::Moore, alive, life).run(Some(0)), Outcome::Undecided { generation: 0 });
+
+        let mut automaton = Automaton::new(block, Neighbourhood::Moore, alive, life);
+        let mut generations = 0;
+        automaton.run_with(None, |_| generations += 1);
+        assert_eq!(generations, 0);
+    }
+
+    #[test]
+    fn cycles() {
+        let to_cells = |rows: &[&str]| {
+            let grid = parse(rows);
+            Grid::new(grid.width(), grid.height(), grid.cells().iter().map(|c| *c == '#').collect())
+        };
+
+        // The glider crosses the torus diagonally, one cell every 4 generations
+        let glider = to_cells(&[".#....", "..#...", "###...", "......", "......", "......"]);
+        let mut automaton = Automaton::with_boundary(glider, Neighbourhood::Moore, Boundary::Toroidal, alive, life);
+        assert_eq!(automaton.run(None), Outcome::Cycle { start: 0, period: 24 });
+
+        // The lonely cell dies first, then only the blinker is left
+        let blinker = to_cells(&[".#....", ".#....", ".#....", "......", "......", ".....#"]);
+        assert_eq!(Automaton::new(blinker, Neighbourhood::Moore, alive, life).run(None), Outcome::Cycle { start: 1, period: 2 });
+    }
+
+    #[test]
+    fn neighbourhoods() {
+        let grid = parse(&["a.b", "...", "c.d"]);
+        let line_of_sight = Neighbourhood::LineOfSight { transparent: |c| *c == '.' };
+
+        assert_eq!(Neighbourhood::Moore.neighbours(&grid, Boundary::Bounded, 0, 0), vec![1, 3, 4]);
+        assert_eq!(Neighbourhood::VonNeumann.neighbours(&grid, Boundary::Bounded, 1, 1), vec![1, 3, 5, 7]);
+        assert_eq!(line_of_sight.neighbours(&grid, Boundary::Bounded, 0, 0), vec![2, 6, 8]);
+        assert_eq!(line_of_sight.neighbours(&grid, Boundary::Bounded, 1, 1), vec![0, 2, 6, 8]);
+    }
+
+    #[test]
+    fn boundaries() {
+        let grid = parse(&["a.b", "...", "c.d"]);
+        let line_of_sight = Neighbourhood::LineOfSight { transparent: |c| *c == '.' };
+
+        assert_eq!(Neighbourhood::VonNeumann.neighbours(&grid, Boundary::Toroidal, 0, 0), vec![6, 2, 1, 3]);
+        assert_eq!(Neighbourhood::VonNeumann.neighbours(&grid, Boundary::Reflective, 0, 0), vec![0, 0, 1, 3]);
+        assert_eq!(Neighbourhood::Moore.neighbours(&grid, Boundary::Reflective, 0, 0), vec![0, 0, 1, 0, 1, 3, 3, 4]);
+
+        // Looking up from `a` wraps around to `c`, looking up and right wraps back to `a` itself
+        assert_eq!(line_of_sight.neighbours(&grid, Boundary::Toroidal, 0, 0), vec![8, 6, 0, 2, 2, 0, 6, 8]);
+        // The middle row and column are floor, looking along them bounces between the mirrors forever
+        assert_eq!(line_of_sight.neighbours(&grid, Boundary::Reflective, 1, 1), vec![0, 2, 6, 8]);
+        // From the end of the row, every line of sight but the vertical ones is reflected back to `a`
+        assert_eq!(line_of_sight.neighbours(&parse(&["a.."]), Boundary::Reflective, 2, 0), vec![0; 6]);
+
+        // Only floor: every line of sight comes back empty
+        let floor = parse(&["..", ".."]);
+        for boundary in [Boundary::Bounded, Boundary::Toroidal, Boundary::Reflective] {
+            assert!(line_of_sight.neighbours(&floor, boundary, 0, 1).is_empty());
+        }
+    }
+}
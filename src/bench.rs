@@ -0,0 +1,230 @@
+use std::collections::BTreeMap;
+use std::fmt::Display;
+use std::fs::{read_to_string, write};
+use std::path::Path;
+use std::time::Duration;
+
+use crate::days::Runner;
+use crate::error::{Error, Result};
+use crate::solver::Part;
+
+/// Summary of the timings of several runs
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Stats {
+    pub min: Duration,
+    pub median: Duration,
+    pub mean: Duration,
+    pub stddev: Duration,
+}
+
+impl Stats {
+    pub fn from_samples(samples: &[Duration]) -> Self {
+        assert!(!samples.is_empty(), "no samples to compute statistics from");
+
+        let mut sorted = samples.to_vec();
+        sorted.sort();
+        let n = sorted.len();
+        let median = if n % 2 == 1 {
+            sorted[n / 2]
+        } else {
+            (sorted[n / 2 - 1] + sorted[n / 2]) / 2
+        };
+
+        let mean = sorted.iter().map(Duration::as_secs_f64).sum::<f64>() / n as f64;
+        let variance = sorted.iter().map(|d| (d.as_secs_f64() - mean).powi(2)).sum::<f64>() / n as f64;
+
+        Self {
+            min: sorted[0],
+            median,
+            mean: Duration::from_secs_f64(mean),
+            stddev: Duration::from_secs_f64(variance.sqrt()),
+        }
+    }
+}
+
+impl Display for Stats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "min {:>10.2?}  median {:>10.2?}  mean {:>10.2?}  stddev {:>10.2?}", self.min, self.median, self.mean, self.stddev)
+    }
+}
+
+/// What is being timed: the parsing of the input or one of the parts
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Step {
+    Parse,
+    Part1,
+    Part2,
+}
+
+impl Step {
+    fn name(&self) -> &'static str {
+        match self {
+            Step::Parse => "parse",
+            Step::Part1 => "part 1",
+            Step::Part2 => "part 2",
+        }
+    }
+
+    fn key(&self) -> &'static str {
+        match self {
+            Step::Parse => "parse",
+            Step::Part1 => "1",
+            Step::Part2 => "2",
+        }
+    }
+
+    fn from_key(key: &str) -> Option<Self> {
+        match key {
+            "parse" => Some(Step::Parse),
+            "1" => Some(Step::Part1),
+            "2" => Some(Step::Part2),
+            _ => None,
+        }
+    }
+}
+
+impl From<Part> for Step {
+    fn from(part: Part) -> Self {
+        match part {
+            Part::One => Step::Part1,
+            Part::Two => Step::Part2,
+        }
+    }
+}
+
+pub struct BenchResult {
+    pub day: u8,
+    pub runs: usize,
+    pub steps: Vec<(Step, Stats)>,
+}
+
+/// Solves the day `warmup + runs` times from scratch, keeping the timings of the last `runs` runs
+pub fn bench(day: u8, runner: Runner, input: &str, part: Option<Part>, warmup: usize, runs: usize) -> Result<BenchResult> {
+    for _ in 0..warmup {
+        runner(day, input, part)?;
+    }
+
+    let mut samples: BTreeMap<Step, Vec<Duration>> = BTreeMap::new();
+    for _ in 0..runs {
+        let result = runner(day, input, part)?;
+        samples.entry(Step::Parse).or_default().push(result.parse_duration);
+        for part in result.parts {
+            samples.entry(Step::from(part.part)).or_default().push(part.duration);
+        }
+    }
+
+    Ok(BenchResult {
+        day,
+        runs,
+        steps: samples.iter().map(|(step, samples)| (*step, Stats::from_samples(samples))).collect(),
+    })
+}
+
+/// Statistics saved by a previous run, per day and step.
+/// Stored as CSV with one `day,step,min_ns,median_ns,mean_ns,stddev_ns` row per day and step.
+#[derive(Default)]
+pub struct Baseline {
+    stats: BTreeMap<(u8, Step), Stats>,
+}
+
+const BASELINE_HEADER: &str = "day,step,min_ns,median_ns,mean_ns,stddev_ns";
+
+impl Baseline {
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = read_to_string(path).map_err(|e| Error::new(format!("{}: {}", path.display(), e)))?;
+
+        let mut stats = BTreeMap::new();
+        for (i, line) in content.lines().enumerate().skip(1) {
+            let error = || Error::new(format!("{}: invalid baseline row '{}'", path.display(), line)).at_line(i + 1);
+
+            let fields: Vec<_> = line.split(',').collect();
+            let [day, step, min, median, mean, stddev] = fields[..] else {
+                return Err(error());
+            };
+
+            let day = day.parse::<u8>().map_err(|_| error())?;
+            let step = Step::from_key(step).ok_or_else(error)?;
+            let nanos = |value: &str| value.parse::<u64>().map(Duration::from_nanos).map_err(|_| error());
+            stats.insert((day, step), Stats {
+                min: nanos(min)?,
+                median: nanos(median)?,
+                mean: nanos(mean)?,
+                stddev: nanos(stddev)?,
+            });
+        }
+
+        Ok(Self { stats })
+    }
+
+    /// Loads the baseline at `path`, or an empty one if the file does not exist yet
+    pub fn load_or_default(path: &Path) -> Result<Self> {
+        if path.exists() {
+            Self::load(path)
+        } else {
+            Ok(Self::default())
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let mut content = String::from(BASELINE_HEADER);
+        content.push('\n');
+        for ((day, step), stats) in &self.stats {
+            content.push_str(&format!(
+                "{},{},{},{},{},{}\n",
+                day,
+                step.key(),
+                stats.min.as_nanos(),
+                stats.median.as_nanos(),
+                stats.mean.as_nanos(),
+                stats.stddev.as_nanos(),
+            ));
+        }
+
+        write(path, content).map_err(|e| Error::new(format!("{}: {}", path.display(), e)))
+    }
+
+    /// Replaces the statistics of the benchmarked day by the new ones
+    pub fn record(&mut self, result: &BenchResult) {
+        for (step, stats) in &result.steps {
+            self.stats.insert((result.day, *step), *stats);
+        }
+    }
+
+    pub fn get(&self, day: u8, step: Step) -> Option<&Stats> {
+        self.stats.get(&(day, step))
+    }
+}
+
+/// Prints the statistics of every step, compared with the baseline when given
+pub fn print(result: &BenchResult, warmup: usize, baseline: Option<&Baseline>) {
+    println!("========= DAY {} ({} runs, {} warm-up) ========", result.day, result.runs, warmup);
+    for (step, stats) in &result.steps {
+        let comparison = match baseline.and_then(|baseline| baseline.get(result.day, *step)) {
+            Some(previous) if !previous.median.is_zero() => {
+                let change = (stats.median.as_secs_f64() / previous.median.as_secs_f64() - 1.0) * 100.0;
+                format!("  ({:+.1}% median vs {:.2?})", change, previous.median)
+            },
+            Some(_) => String::new(),
+            None if baseline.is_some() => "  (not in baseline)".to_string(),
+            None => String::new(),
+        };
+        println!("{:<7} {}{}", step.name(), stats, comparison);
+    }
+    println!();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stats() {
+        let samples = [4, 1, 3, 2].map(Duration::from_micros);
+        let stats = Stats::from_samples(&samples);
+
+        assert_eq!(stats.min, Duration::from_micros(1));
+        assert_eq!(stats.median, Duration::from_nanos(2500));
+        assert_eq!(stats.mean, Duration::from_nanos(2500));
+        assert_eq!(stats.stddev.as_nanos(), 1118);
+    }
+}
@@ -1,23 +1,116 @@
-#![allow(dead_code)]
-
-use advent::day10;
-use advent::day11;
-use advent::day13;
-use advent::day19;
-use advent::day20;
-use advent::day22;
-use advent::day23;
-use advent::day24;
-use advent::day25;
-
-fn main() {
-    day10::Solution::init().solve();
-    day11::Solution::init().solve();
-    day13::Solution::init().solve();
-    day19::Solution::init().solve();
-    day20::Solution::init().solve();
-    day22::Solution::init().solve();
-    day23::Solution::init().solve();
-    day24::Solution::init().solve();
-    day25::Solution::init().solve();
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::thread::available_parallelism;
+use std::time::{Duration, Instant};
+
+use advent::days::DAYS;
+use advent::answers::{self, Answers};
+use advent::report::Format;
+use advent::runner;
+
+const USAGE: &str = "\
+Usage: all [--jobs <N>] [--format <FORMAT>] [--check]
+
+Solves every implemented day, running independent days concurrently.
+
+Options:
+  -j, --jobs <N>    Number of days solved at the same time, defaults to the number of CPUs
+  -f, --format <F>  Output format: `text` (default), `json` (one object per line) or `csv`
+  -c, --check       Compare the answers with the expected ones, failing on mismatch
+      --answers <P> Expected answers file used by `--check`, defaults to `answers.toml`
+  -h, --help        Print this help";
+
+struct Args {
+    jobs: usize,
+    format: Format,
+    answers: Option<PathBuf>,
+    help: bool,
+}
+
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Args, String> {
+    let mut parsed = Args {
+        jobs: available_parallelism().map_or(1, |n| n.get()),
+        format: Format::Text,
+        answers: None,
+        help: false,
+    };
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-h" | "--help" => parsed.help = true,
+            "-j" | "--jobs" => {
+                let value = args.next().ok_or("missing value for `--jobs`")?;
+                parsed.jobs = match value.parse() {
+                    Ok(jobs) if jobs > 0 => jobs,
+                    _ => return Err(format!("invalid number of jobs `{}`", value)),
+                };
+            },
+            "-c" | "--check" => parsed.answers = parsed.answers.or_else(|| Some(answers::default_path())),
+            "--answers" => {
+                let value = args.next().ok_or("missing value for `--answers`")?;
+                parsed.answers = Some(PathBuf::from(value));
+            },
+            "-f" | "--format" => {
+                let value = args.next().ok_or("missing value for `--format`")?;
+                parsed.format = Format::try_from(value.as_str()).map_err(|_| format!("invalid format `{}`, expected text, json or csv", value))?;
+            },
+            _ => return Err(format!("unknown argument `{}`", arg)),
+        }
+    }
+
+    Ok(parsed)
+}
+
+fn main() -> ExitCode {
+    let args = match parse_args(std::env::args().skip(1)) {
+        Ok(args) => args,
+        Err(message) => {
+            eprintln!("error: {}\n\n{}", message, USAGE);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    if args.help {
+        println!("{}", USAGE);
+        return ExitCode::SUCCESS;
+    }
+
+    let answers = match args.answers.as_deref().map(Answers::load).transpose() {
+        Ok(answers) => answers,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let start = Instant::now();
+    let mut results = runner::run_parallel(DAYS, args.jobs, None);
+    let wall_clock = start.elapsed();
+
+    let mut failed = false;
+    let mut cpu_time = Duration::ZERO;
+    args.format.print_header();
+    for (day, result) in &mut results {
+        match result {
+            Ok(result) => {
+                if let Some(answers) = &answers {
+                    answers.check(result);
+                }
+                failed |= result.has_failed_check();
+                cpu_time += result.total_duration();
+            },
+            Err(_) => failed = true,
+        }
+        args.format.print(*day, result);
+    }
+
+    if args.format == Format::Text {
+        println!("Total: {:?} wall-clock, {:?} summed over the days ({} jobs)", wall_clock, cpu_time, args.jobs);
+    }
+
+    if failed {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
 }
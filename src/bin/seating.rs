@@ -0,0 +1,137 @@
+use std::process::ExitCode;
+use std::thread::available_parallelism;
+use std::time::Instant;
+
+use rand::{Rng, SeedableRng};
+use rand::rngs::StdRng;
+
+use advent::automaton::{Boundary, Outcome};
+use advent::bench::Stats;
+use advent::day11::{self, Rules};
+
+const USAGE: &str = "\
+Usage: seating [--size <N>] [--threads <N>] [--runs <N>] [--rules <RULES>] [--seed <N>]
+
+Benchmarks the day 11 seating on a random square floor plan, stepping each generation
+on a single thread and then splitting its cells over several threads. Only the steps are
+timed, the floor plan being parsed and the seating built beforehand.
+
+Options:
+  -s, --size <N>      Number of tiles per side, defaults to 1000
+  -t, --threads <N>   Number of threads of the parallel runs, defaults to the number of CPUs
+  -n, --runs <N>      Number of timed runs of each version, defaults to 3
+  -r, --rules <R>     `adjacent` (part 1, default) or `visible` (part 2)
+      --seed <N>      Seed of the random floor plan, defaults to 11
+  -h, --help          Print this help";
+
+struct Args {
+    size: usize,
+    threads: usize,
+    runs: usize,
+    rules: Rules,
+    seed: u64,
+    help: bool,
+}
+
+fn parse_number<T: std::str::FromStr>(option: &str, value: Option<String>) -> Result<T, String> {
+    let value = value.ok_or_else(|| format!("missing value for `{}`", option))?;
+    value.parse().map_err(|_| format!("invalid value `{}` for `{}`", value, option))
+}
+
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Args, String> {
+    let mut parsed = Args {
+        size: 1000,
+        threads: available_parallelism().map_or(1, |n| n.get()),
+        runs: 3,
+        rules: Rules::Adjacent,
+        seed: 11,
+        help: false,
+    };
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-h" | "--help" => parsed.help = true,
+            "-s" | "--size" => parsed.size = parse_number("--size", args.next())?,
+            "-t" | "--threads" => parsed.threads = parse_number("--threads", args.next())?,
+            "-n" | "--runs" => parsed.runs = parse_number("--runs", args.next())?,
+            "-r" | "--rules" => {
+                let value = args.next().ok_or("missing value for `--rules`")?;
+                parsed.rules = Rules::try_from(value.as_str()).map_err(|_| format!("invalid rules `{}`, expected adjacent or visible", value))?;
+            },
+            "--seed" => parsed.seed = parse_number("--seed", args.next())?,
+            _ => return Err(format!("unknown argument `{}`", arg)),
+        }
+    }
+
+    if parsed.size == 0 || parsed.threads == 0 || parsed.runs == 0 {
+        return Err("`--size`, `--threads` and `--runs` must be at least 1".to_string());
+    }
+
+    Ok(parsed)
+}
+
+/// A square floor plan with three seats out of four
+fn floor_plan(size: usize, seed: u64) -> String {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut plan = String::with_capacity((size + 1) * size);
+    for _ in 0..size {
+        plan.extend((0..size).map(|_| if rng.gen_bool(0.75) { 'L' } else { '.' }));
+        plan.push('\n');
+    }
+    plan
+}
+
+fn main() -> ExitCode {
+    let args = match parse_args(std::env::args().skip(1)) {
+        Ok(args) => args,
+        Err(message) => {
+            eprintln!("error: {}\n\n{}", message, USAGE);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    if args.help {
+        println!("{}", USAGE);
+        return ExitCode::SUCCESS;
+    }
+
+    let plan = floor_plan(args.size, args.seed);
+
+    // A cycling seating keeps changing, so its steps are only timed until the cycle first closes
+    let (outcome, occupied) = day11::simulate(&plan, args.rules, Boundary::Bounded, 1).unwrap();
+    let generations = match outcome {
+        Outcome::Stable { generation } => generation + 1,
+        Outcome::Cycle { start, period } => start + period,
+        Outcome::Undecided { generation } => generation,
+    };
+
+    let mut results = vec![];
+    let mut medians = vec![];
+    for threads in [1, args.threads] {
+        let mut samples = vec![];
+        for _ in 0..args.runs {
+            let mut seating = day11::Seating::new(&plan, args.rules, Boundary::Bounded, threads).unwrap();
+            let start = Instant::now();
+            while seating.generation() < generations && seating.step() {}
+            samples.push(start.elapsed());
+            results.push((seating.generation(), seating.occupied()));
+        }
+
+        let stats = Stats::from_samples(&samples);
+        println!("{:>2} thread(s)  {}", threads, stats);
+        medians.push(stats.median);
+    }
+
+    if results.iter().any(|result| *result != results[0]) {
+        eprintln!("error: the parallel runs differ from the serial one");
+        return ExitCode::FAILURE;
+    }
+
+    let rules = match args.rules {
+        Rules::Adjacent => "adjacent",
+        Rules::Visible => "visible",
+    };
+    println!("{}x{} floor plan, {} rules: {}, {} seats occupied", args.size, args.size, rules, outcome, occupied);
+    println!("Speed-up: {:.2}x (median)", medians[0].as_secs_f64() / medians[1].as_secs_f64());
+    ExitCode::SUCCESS
+}
@@ -1,75 +1,493 @@
-use std::collections::HashMap;
-use std::fs::read_to_string;
-use std::io::{self, Write};
+use std::collections::{BTreeMap, HashMap};
 
 #[allow(unused_imports)]
 use itertools::Itertools;
+use num::bigint::RandBigInt;
+use petgraph::algo::{astar, toposort};
+use petgraph::dot::Dot;
+use petgraph::graph::{DiGraph, NodeIndex};
+use num::{BigUint, Zero};
+use rand::Rng;
+
+use crate::error::{Error, Result};
+use crate::input;
+use crate::solver::Solver;
 
 pub struct Solution {
     lines: Vec<String>,
     num: Vec<i32>,
 }
 
-impl Solution {
-    pub fn init() -> Self {
-        let mut lines = Vec::new();
-        for line in read_to_string("inputs/day10.txt").unwrap().lines() {
-            lines.push(line.to_string());
+impl Solver for Solution {
+    type Answer1 = usize;
+    type Answer2 = BigUint;
+
+    fn parse(input: &str) -> Result<Self> {
+        let lines = input::lines(input);
+        let mut num = Vec::new();
+        for (i, line) in lines.iter().enumerate() {
+            let value = line.parse::<i32>().ok().filter(|&value| value >= 1)
+                .ok_or_else(|| Error::new(format!("invalid joltage rating '{}', expected a positive integer", line)).at_line(lines.line_number(i)))?;
+            num.push(value);
+        }
+
+        Ok(Self {
+            num: num.into_iter().sorted().collect(),
+            lines: lines.iter().map(|line| line.to_string()).collect(),
+        })
+    }
+
+    fn part1(&self) -> Result<usize> {
+        let analysis = analyse(&self.num, 3)?;
+        Ok(analysis.differences.get(&1).unwrap_or(&0) * analysis.differences.get(&3).unwrap_or(&0))
+    }
+
+    fn part2(&self) -> Result<BigUint> {
+        Ok(analyse(&self.num, 3)?.arrangements)
+    }
+}
+
+/// What can be done with a bag of adapters, each accepting an input up to `max_gap` jolts below its rating
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Analysis {
+    /// Rating of the device, `max_gap` above the highest adapter
+    pub device: i32,
+    /// Number of gaps of each size when chaining every adapter from the outlet to the device
+    pub differences: BTreeMap<i32, usize>,
+    /// Number of distinct chains from the outlet to the device
+    pub arrangements: BigUint,
+}
+
+/// Analyses the adapters, given in any order, for an outlet of 0 jolts and the given maximum gap
+pub fn analyse(adapters: &[i32], max_gap: i32) -> Result<Analysis> {
+    let chains = Chains::from_ratings(chain(adapters, max_gap)?, max_gap);
+    let ratings = &chains.ratings;
+    Ok(Analysis {
+        device: *ratings.last().unwrap(),
+        differences: ratings.iter().tuple_windows().map(|(a, b)| b - a).counts().into_iter().collect(),
+        arrangements: chains.count().clone(),
+    })
+}
+
+/// Every rating from the outlet to the device, sorted
+fn chain(adapters: &[i32], max_gap: i32) -> Result<Vec<i32>> {
+    if max_gap < 1 {
+        return Err(Error::new(format!("invalid maximum gap {}, it must be at least 1 jolt", max_gap)));
+    }
+
+    let mut chain = Vec::with_capacity(adapters.len() + 2);
+    chain.push(0);
+    chain.extend(adapters.iter().copied().sorted());
+    let device = chain.last().unwrap().checked_add(max_gap)
+        .ok_or_else(|| Error::new("the device rating does not fit in an i32"))?;
+    chain.push(device);
+    Ok(chain)
+}
+
+/// Number of distinct chains of the adapters, given in any order, modulo `modulus`.
+/// Much cheaper than the exact count for large bags of adapters. Any modulus works, not only primes.
+pub fn arrangements_modulo(adapters: &[i32], max_gap: i32, modulus: u64) -> Result<u64> {
+    if modulus < 1 {
+        return Err(Error::new("invalid modulus 0, it must be at least 1"));
+    }
+
+    // Sums are done in u128 as two residues can overflow a u64 for moduli above 2^63
+    let add = |a: u64, b: &u64| ((a as u128 + *b as u128) % modulus as u128) as u64;
+    let remaining = count_remaining(&chain(adapters, max_gap)?, max_gap, 1 % modulus, add);
+    Ok(remaining[0])
+}
+
+/// `remaining[i]` is the number of ways to go from the i-th rating of the chain to the last one,
+/// summed from the ratings at most `max_gap` above it with `add`
+fn count_remaining<T: Clone + Default>(chain: &[i32], max_gap: i32, one: T, add: impl Fn(T, &T) -> T) -> Vec<T> {
+    let last = chain.len() - 1;
+    let mut remaining = vec![T::default(); chain.len()];
+    remaining[last] = one;
+
+    for i in (0..last).rev() {
+        remaining[i] = (i + 1..chain.len())
+            .take_while(|&j| chain[j] - chain[i] <= max_gap)
+            .fold(T::default(), |sum, j| add(sum, &remaining[j]));
+    }
+
+    remaining
+}
+
+/// Every chain of a bag of adapters from the outlet to the device
+pub struct Chains {
+    ratings: Vec<i32>,
+    max_gap: i32,
+    /// `remaining[i]` is the number of ways to go from the i-th rating to the device
+    remaining: Vec<BigUint>,
+}
+
+impl Chains {
+    pub fn new(adapters: &[i32], max_gap: i32) -> Result<Self> {
+        Ok(Self::from_ratings(chain(adapters, max_gap)?, max_gap))
+    }
+
+    /// Counts the chains through the given ratings, from the outlet to the device
+    fn from_ratings(ratings: Vec<i32>, max_gap: i32) -> Self {
+        let remaining = count_remaining(&ratings, max_gap, BigUint::from(1u32), |a, b| a + b);
+        Self { ratings, max_gap, remaining }
+    }
+
+    /// Number of chains, the same as `Analysis::arrangements`
+    pub fn count(&self) -> &BigUint {
+        &self.remaining[0]
+    }
+
+    /// Lazily iterates over the chains in lexicographic order
+    pub fn iter(&self) -> ChainIter<'_> {
+        ChainIter { chains: self, path: vec![], started: false }
+    }
+
+    /// The chain at index `k` (from 0) in lexicographic order
+    pub fn nth(&self, mut k: BigUint) -> Option<Vec<i32>> {
+        if k >= *self.count() {
+            return None;
         }
 
-        Self {
-            num: lines.iter().map(|x| x.parse().unwrap()).into_iter().sorted().collect(),
-            lines,
+        let mut path = vec![0];
+        while let Some(i) = path.last().copied().filter(|&i| i != self.device()) {
+            for j in self.successors(i) {
+                if k < self.remaining[j] {
+                    path.push(j);
+                    break;
+                }
+                k -= &self.remaining[j];
+            }
+        }
+
+        Some(self.adapters(&path))
+    }
+
+    /// A chain drawn uniformly at random, if there is any
+    pub fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> Option<Vec<i32>> {
+        if self.count().is_zero() {
+            return None;
         }
+        self.nth(rng.gen_biguint_below(self.count()))
+    }
+
+    fn device(&self) -> usize {
+        self.ratings.len() - 1
+    }
+
+    /// Indices of the ratings that can follow the i-th one and still reach the device, in increasing order
+    fn successors(&self, i: usize) -> impl Iterator<Item = usize> + '_ {
+        (i + 1..self.ratings.len())
+            .take_while(move |&j| self.ratings[j] - self.ratings[i] <= self.max_gap)
+            .filter(|&j| !self.remaining[j].is_zero())
+    }
+
+    /// Ratings of the adapters along the path, without the outlet and the device
+    fn adapters(&self, path: &[usize]) -> Vec<i32> {
+        path[1..path.len() - 1].iter().map(|&i| self.ratings[i]).collect()
     }
+}
+
+/// Iterator over the chains, see `Chains::iter`
+pub struct ChainIter<'a> {
+    chains: &'a Chains,
+    /// Indices of the ratings of the last chain returned
+    path: Vec<usize>,
+    started: bool,
+}
+
+impl Iterator for ChainIter<'_> {
+    type Item = Vec<i32>;
 
-    fn part1(&mut self) -> i32 {
-        let mut last = 0;
-        let mut differences = HashMap::new();
-        differences.insert(3, 1);
+    fn next(&mut self) -> Option<Vec<i32>> {
+        if !self.started {
+            self.started = true;
+            if self.chains.count().is_zero() {
+                return None;
+            }
+            self.path.push(0);
+        } else {
+            // Backtrack to the last rating that can be followed by a larger one than in the previous chain
+            loop {
+                let previous = self.path.pop()?;
+                let &i = self.path.last()?;
+                if let Some(j) = self.chains.successors(i).find(|&j| j > previous) {
+                    self.path.push(j);
+                    break;
+                }
+            }
+        }
 
-        for value in &self.num {
-            let diff = value - last;
-            *differences.entry(diff).or_insert(0) += 1;
-            last = *value;
+        // Then complete the chain with the smallest ratings
+        while let Some(i) = self.path.last().copied().filter(|&i| i != self.chains.device()) {
+            self.path.push(self.chains.successors(i).next().unwrap());
         }
 
-        *differences.get(&1).unwrap_or(&0) * *differences.get(&3).unwrap_or(&0)
+        Some(self.chains.adapters(&self.path))
     }
+}
+
+/// Which adapter can be plugged into which, from the outlet to the device.
+/// Nodes are weighted by their rating and edges by the gap they bridge.
+pub struct AdapterGraph {
+    graph: DiGraph<i32, i32>,
+    outlet: NodeIndex,
+    device: NodeIndex,
+}
+
+impl AdapterGraph {
+    pub fn new(adapters: &[i32], max_gap: i32) -> Result<Self> {
+        let ratings = chain(adapters, max_gap)?;
+        let mut graph = DiGraph::new();
+        let nodes: Vec<_> = ratings.iter().map(|&rating| graph.add_node(rating)).collect();
+
+        for i in 0..ratings.len() {
+            for j in (i + 1..ratings.len()).take_while(|&j| ratings[j] - ratings[i] <= max_gap) {
+                graph.add_edge(nodes[i], nodes[j], ratings[j] - ratings[i]);
+            }
+        }
 
-    fn part2(&mut self) -> i64 {
-        let last = self.num.last().unwrap() + 3;
+        Ok(Self {
+            graph,
+            outlet: nodes[0],
+            device: *nodes.last().unwrap(),
+        })
+    }
+
+    pub fn graph(&self) -> &DiGraph<i32, i32> {
+        &self.graph
+    }
+
+    /// Graphviz description of the graph, e.g. for `dot -Tsvg`
+    pub fn to_dot(&self) -> String {
+        format!("{}", Dot::new(&self.graph))
+    }
+
+    /// A chain using as few adapters as possible, if the device can be reached
+    pub fn shortest_chain(&self) -> Option<Vec<i32>> {
+        let (_, path) = astar(&self.graph, self.outlet, |node| node == self.device, |_| 1, |_| 0)?;
+        Some(self.adapters(&path))
+    }
 
-        self.num.push(last);
-        
-        let mut dp: HashMap<i32, i64> = HashMap::from_iter(self.num.iter().map(|&x| (x, 0)));
-        dp.insert(0, 1);
+    /// A chain using as many adapters as possible, if the device can be reached
+    pub fn longest_chain(&self) -> Option<Vec<i32>> {
+        // The graph is acyclic since edges always go to a later adapter, so longest paths follow a topological order
+        let order = toposort(&self.graph, None).expect("the adapter graph has no cycle");
 
-        for current in &self.num {
-            for possible_origin in *current-3..*current {
-                *dp.get_mut(current).unwrap() += *dp.get(&possible_origin).unwrap_or(&0);
+        // Length of the longest path from the outlet to each node, and the node before it on that path
+        let mut longest: HashMap<NodeIndex, (usize, NodeIndex)> = HashMap::new();
+        longest.insert(self.outlet, (0, self.outlet));
+        for node in order {
+            let Some(&(length, _)) = longest.get(&node) else {
+                continue;
+            };
+            for next in self.graph.neighbors(node) {
+                if longest.get(&next).is_none_or(|&(best, _)| length + 1 > best) {
+                    longest.insert(next, (length + 1, node));
+                }
             }
         }
 
-        *dp.get(&last).unwrap()
+        longest.get(&self.device)?;
+        let mut path = vec![self.device];
+        while *path.last().unwrap() != self.outlet {
+            path.push(longest[path.last().unwrap()].1);
+        }
+        path.reverse();
+
+        Some(self.adapters(&path))
+    }
+
+    /// Ratings of the adapters along the path, without the outlet and the device
+    fn adapters(&self, path: &[NodeIndex]) -> Vec<i32> {
+        path[1..path.len() - 1].iter().map(|&node| self.graph[node]).collect()
     }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SMALL_EXAMPLE: &str = "\
+16
+10
+15
+5
+1
+11
+7
+19
+6
+12
+4
+";
 
-    pub fn solve(&mut self) {
-        println!("========= DAY 10 ========");
-        print!("Solving part 1: ");
-        io::stdout().flush().unwrap();
+    const LARGE_EXAMPLE: &str = "\
+28
+33
+18
+42
+31
+14
+46
+20
+48
+47
+24
+23
+49
+45
+19
+38
+39
+11
+1
+32
+25
+35
+8
+17
+7
+9
+4
+2
+34
+10
+3
+";
 
-        let start = std::time::Instant::now();
-        let part1 = self.part1();
-        let part1_time = start.elapsed();
-        println!("{:?} (took {:?})", part1, part1_time);
+    #[test]
+    fn part1_examples() {
+        assert_eq!(Solution::parse(SMALL_EXAMPLE).unwrap().part1().unwrap(), 7 * 5);
+        assert_eq!(Solution::parse(LARGE_EXAMPLE).unwrap().part1().unwrap(), 22 * 10);
+    }
+
+    #[test]
+    fn part2_examples() {
+        assert_eq!(Solution::parse(SMALL_EXAMPLE).unwrap().part2().unwrap(), BigUint::from(8u32));
+        assert_eq!(Solution::parse(LARGE_EXAMPLE).unwrap().part2().unwrap(), BigUint::from(19208u32));
+    }
 
-        print!("Solving part 2: ");
-        io::stdout().flush().unwrap();
-        let start = std::time::Instant::now();
-        let part2 = self.part2();
-        let part2_time = start.elapsed();
-        println!("{:?} (took {:?})", part2, part2_time);
-        println!();
+    #[test]
+    fn parts_are_independent() {
+        let solution = Solution::parse(SMALL_EXAMPLE).unwrap();
+        assert_eq!(solution.part2().unwrap(), BigUint::from(8u32));
+        assert_eq!(solution.part2().unwrap(), BigUint::from(8u32));
+        assert_eq!(solution.part1().unwrap(), 7 * 5);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn other_gaps() {
+        let adapters = [1, 2, 3, 5, 6];
+
+        let analysis = analyse(&adapters, 1).unwrap();
+        assert_eq!(analysis.device, 7);
+        assert_eq!(analysis.differences, BTreeMap::from([(1, 5), (2, 1)]));
+        assert_eq!(analysis.arrangements, BigUint::from(0u32));
+
+        let analysis = analyse(&adapters, 2).unwrap();
+        assert_eq!(analysis.device, 8);
+        assert_eq!(analysis.differences, BTreeMap::from([(1, 4), (2, 2)]));
+        assert_eq!(analysis.arrangements, BigUint::from(3u32));
+
+        let analysis = analyse(&[3, 1, 2], 4).unwrap();
+        assert_eq!(analysis.differences, BTreeMap::from([(1, 3), (4, 1)]));
+        assert_eq!(analysis.arrangements, BigUint::from(4u32));
+    }
+
+    #[test]
+    fn large_bags() {
+        // Every adapter from 1 to 300: the counts are the tribonacci numbers, far beyond 64 bits
+        let adapters: Vec<_> = (1..=300).collect();
+        let analysis = analyse(&adapters, 3).unwrap();
+        assert!(analysis.arrangements.bits() > 64);
+
+        let prime = 1_000_000_007;
+        assert_eq!(BigUint::from(arrangements_modulo(&adapters, 3, prime).unwrap()), &analysis.arrangements % prime);
+        assert_eq!(arrangements_modulo(&adapters, 3, 1).unwrap(), 0);
+
+        let largest_prime = 18_446_744_073_709_551_557;
+        assert_eq!(arrangements_modulo(&adapters, 3, largest_prime).unwrap(), 14_775_746_092_297_300_593);
+        assert_eq!(BigUint::from(arrangements_modulo(&adapters, 3, largest_prime).unwrap()), analysis.arrangements % largest_prime);
+    }
+
+    #[test]
+    fn chains() {
+        let solution = Solution::parse(SMALL_EXAMPLE).unwrap();
+        let chains = Chains::new(&solution.num, 3).unwrap();
+        assert_eq!(*chains.count(), BigUint::from(8u32));
+
+        let all: Vec<_> = chains.iter().collect();
+        assert_eq!(all.len(), 8);
+        assert_eq!(all[0], vec![1, 4, 5, 6, 7, 10, 11, 12, 15, 16, 19]);
+        assert_eq!(all[7], vec![1, 4, 7, 10, 12, 15, 16, 19]);
+        assert!(all.windows(2).all(|pair| pair[0] < pair[1]));
+
+        for (k, chain) in all.iter().enumerate() {
+            assert_eq!(chains.nth(BigUint::from(k)).as_ref(), Some(chain));
+        }
+        assert_eq!(chains.nth(BigUint::from(8u32)), None);
+    }
+
+    #[test]
+    fn sampling() {
+        use rand::SeedableRng;
+
+        let solution = Solution::parse(SMALL_EXAMPLE).unwrap();
+        let chains = Chains::new(&solution.num, 3).unwrap();
+        let all: Vec<_> = chains.iter().collect();
+
+        let mut rng = rand::rngs::StdRng::seed_from_u64(10);
+        let mut seen = vec![0; all.len()];
+        for _ in 0..800 {
+            let chain = chains.sample(&mut rng).unwrap();
+            seen[all.iter().position(|c| *c == chain).unwrap()] += 1;
+        }
+        assert!(seen.iter().all(|&n| n > 50), "{:?}", seen);
+
+        assert_eq!(Chains::new(&[1, 5], 3).unwrap().sample(&mut rng), None);
+        assert_eq!(Chains::new(&[1, 5], 3).unwrap().iter().count(), 0);
+    }
+
+    #[test]
+    fn graph() {
+        let solution = Solution::parse(SMALL_EXAMPLE).unwrap();
+        let graph = AdapterGraph::new(&solution.num, 3).unwrap();
+
+        assert_eq!(graph.graph().node_count(), 13);
+        assert_eq!(graph.longest_chain(), Some(solution.num.clone()));
+        assert_eq!(graph.shortest_chain(), Some(vec![1, 4, 7, 10, 12, 15, 16, 19]));
+
+        let dot = graph.to_dot();
+        assert!(dot.starts_with("digraph {"));
+        assert!(dot.contains("label = \"22\""));
+
+        let unreachable = AdapterGraph::new(&[1, 5], 3).unwrap();
+        assert_eq!(unreachable.shortest_chain(), None);
+        assert_eq!(unreachable.longest_chain(), None);
+
+        // Every adapter accepts the outlet and every lower adapter, but only the highest reaches the device
+        let adapters: Vec<_> = (1..=100).collect();
+        let wide = AdapterGraph::new(&adapters, 100_000_000).unwrap();
+        assert_eq!(wide.graph().edge_count(), 101 * 100 / 2 + 1);
+        assert_eq!(wide.shortest_chain(), Some(vec![100]));
+    }
+
+    #[test]
+    fn invalid_rating() {
+        let error = Solution::parse("1\n2\nthree\n").err().unwrap();
+        assert_eq!(error.line, Some(3));
+        let error = Solution::parse("1\n0\n").err().unwrap();
+        assert_eq!(error.line, Some(2));
+        let error = Solution::parse("-3\n1\n").err().unwrap();
+        assert_eq!(error.line, Some(1));
+    }
+
+    #[test]
+    fn invalid_parameters() {
+        assert!(analyse(&[1, 2], 0).is_err());
+        assert!(analyse(&[1, i32::MAX - 2], 3).is_err());
+        assert!(Chains::new(&[1, 2], -1).is_err());
+        assert!(AdapterGraph::new(&[i32::MAX], 1).is_err());
+        assert!(arrangements_modulo(&[1, 2], 3, 0).is_err());
+    }
+}
@@ -1,29 +1,39 @@
-use std::fs::read_to_string;
-use std::io::{self, Write};
-use std::collections::HashMap;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::thread::sleep;
+use std::time::Duration;
 
 #[allow(unused_imports)]
 use itertools::Itertools;
 
+use crate::automaton::{Automaton, Boundary, Grid, Neighbourhood, Outcome, Rule};
+use crate::days::{Export, Visualization};
+use crate::error::{Error, Result};
+use crate::image::{Rgb, RgbImage};
+use crate::input::{self, Block};
+use crate::solver::{Part, Solver};
+
 pub struct Solution {
     lines: Vec<String>,
-    map: Map,
+    grid: Grid<Tile>,
 }
 
-#[derive(PartialEq, Clone)]
+#[derive(Copy, Clone, PartialEq, Eq, Hash)]
 enum Tile {
     Empty,
     Seat,
     OccupiedSeat,
 }
 
-impl From<char> for Tile {
-    fn from(value: char) -> Self {
+impl TryFrom<char> for Tile {
+    type Error = Error;
+
+    fn try_from(value: char) -> Result<Self> {
         match value {
-            'L' => Tile::Seat,
-            '#' => Tile::OccupiedSeat,
-            '.' => Tile::Empty,
-            _ => unreachable!(),
+            'L' => Ok(Tile::Seat),
+            '#' => Ok(Tile::OccupiedSeat),
+            '.' => Ok(Tile::Empty),
+            _ => Err(Error::new(format!("invalid tile character '{}'", value))),
         }
     }
 }
@@ -36,220 +46,356 @@ impl Tile {
             Tile::Empty => '.',
         }
     }
-}
 
-#[derive(Clone)]
-struct Map {
-    tiles: Vec<Vec<Tile>>,
-    seat_positions: Vec<(i32, i32)>,
-    occupied_seat_map: HashMap<((i32, i32), (i32, i32)), (i32, i32)>,
+    fn rgb(&self) -> Rgb {
+        match self {
+            Tile::Empty => [48, 48, 48],
+            Tile::Seat => [46, 160, 67],
+            Tile::OccupiedSeat => [218, 54, 51],
+        }
+    }
 
-    width: i32,
-    height: i32,
+    /// ANSI colour of the tile: grey floor, green free seats and red occupied ones
+    fn ansi_colour(&self) -> &'static str {
+        match self {
+            Tile::Empty => "\x1b[90m",
+            Tile::Seat => "\x1b[32m",
+            Tile::OccupiedSeat => "\x1b[31m",
+        }
+    }
 }
 
-impl From<&Vec<String>> for Map {
-    fn from(value: &Vec<String>) -> Self {
-        let mut seat_positions = vec![];
-        let mut tiles = vec![];
+fn parse_grid(lines: &Block) -> Result<Grid<Tile>> {
+    if lines.is_empty() {
+        return Err(Error::new("empty seat layout"));
+    }
 
-        for (y, line) in value.iter().enumerate() {
-            let mut cur = vec![];
-            for (x, c) in line.chars().enumerate() {
-                let v = Tile::from(c);
-                if v != Tile::Empty {
-                    seat_positions.push((x as i32, y as i32));
-                }
-                cur.push(v);
-            }
-            tiles.push(cur);
+    let mut tiles = vec![];
+    for (y, line) in lines.iter().enumerate() {
+        if line.len() != lines[0].len() {
+            return Err(Error::new(format!("expected a row of {} tiles, found {}", lines[0].len(), line.len())).at_line(lines.line_number(y)));
         }
 
-        let height = tiles.len() as i32;
-        let width = tiles[0].len() as i32;
-
-        // Part 2 map 
-        let mut occupied_seat_map: HashMap<((i32, i32), (i32, i32)), (i32, i32)> = HashMap::new();
-        for (x, y) in &seat_positions {
-            for dx in -1..=1 {
-                for dy in -1..=1 {
-                    if (dx, dy) == (0, 0) {
-                        continue;
-                    }
-
-                    let (mut nx, mut ny) = (x + dx, y + dy);
-                    while nx >= 0 && nx < width && ny >= 0 && ny < height {
-                        if tiles[ny as usize][nx as usize] != Tile::Empty {
-                            occupied_seat_map.insert(((*x, *y), (dx, dy)), (nx, ny));
-                            break;
-                        }
-
-                        nx += dx;
-                        ny += dy;
-                    }
-                }
-            }
+        for (x, c) in line.chars().enumerate() {
+            tiles.push(Tile::try_from(c).map_err(|e| e.at_line(lines.line_number(y)).at_column(x + 1))?);
         }
+    }
 
-        Self {
-            height,
-            width,
+    Ok(Grid::new(lines[0].len(), lines.len(), tiles))
+}
 
-            tiles,
-            seat_positions,
-            occupied_seat_map,
-        }
+/// Seats become occupied when no neighbour is, and free when at least `tolerance` neighbours are
+fn seating_rule(tolerance: usize) -> impl Rule<Tile> {
+    move |tile, occupied| match tile {
+        Tile::Seat if occupied == 0 => Tile::OccupiedSeat,
+        Tile::OccupiedSeat if occupied >= tolerance => Tile::Seat,
+        tile => *tile,
     }
 }
 
-impl std::cmp::PartialEq for Map {
-    fn eq(&self, other: &Self) -> bool {
-        self.tiles == other.tiles
+fn is_occupied(tile: &Tile) -> bool {
+    *tile == Tile::OccupiedSeat
+}
+
+/// Seats looking at the adjacent seats, as in part 1
+fn adjacent_seating(grid: Grid<Tile>, boundary: Boundary) -> Automaton<Tile, impl Rule<Tile>> {
+    Automaton::with_boundary(grid, Neighbourhood::Moore, boundary, is_occupied, seating_rule(4))
+}
+
+/// Seats looking at the first seat in each direction, as in part 2
+fn visible_seating(grid: Grid<Tile>, boundary: Boundary) -> Automaton<Tile, impl Rule<Tile>> {
+    let neighbourhood = Neighbourhood::LineOfSight { transparent: |tile| *tile == Tile::Empty };
+    Automaton::with_boundary(grid, neighbourhood, boundary, is_occupied, seating_rule(5))
+}
+
+/// Number of occupied seats once the seating is stable, or a description of the cycle the seating enters
+fn settle<R: Rule<Tile>>(seating: Automaton<Tile, R>) -> Result<usize> {
+    match run(seating) {
+        (Outcome::Stable { .. }, occupied) => Ok(occupied),
+        (outcome, _) => Err(Error::new(format!("the seating never settles, it {}", outcome))),
     }
 }
 
-impl Map {
-    fn print(&self) {
-        for line in &self.tiles {
-            for tile in line {
-                print!("{}", tile.as_char());
-            }
-            println!();
+/// How the seating ended, and the number of occupied seats at that point
+fn run<R: Rule<Tile>>(mut seating: Automaton<Tile, R>) -> (Outcome, usize) {
+    let outcome = seating.run(None);
+    (outcome, count_occupied(seating.grid()))
+}
+
+/// The seats people look at before sitting down or leaving
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Rules {
+    /// The adjacent seats, as in part 1
+    Adjacent,
+    /// The first seat in each direction, as in part 2
+    Visible,
+}
+
+impl TryFrom<&str> for Rules {
+    type Error = ();
+
+    fn try_from(value: &str) -> std::result::Result<Self, Self::Error> {
+        match value {
+            "adjacent" => Ok(Rules::Adjacent),
+            "visible" => Ok(Rules::Visible),
+            _ => Err(()),
         }
     }
+}
 
-    fn count_occupied_adjacent(&self, (x, y): (i32, i32)) -> usize {
-        let mut res = 0;
-        for dx in -1..=1 {
-            for dy in -1..=1 {
-                let (nx, ny) = (x + dx, y + dy);
+/// The seating of a layout, which can be stepped one generation at a time
+pub struct Seating {
+    automaton: Automaton<Tile, Box<dyn Rule<Tile>>>,
+}
 
-                if (dx, dy) != (0, 0) && nx >= 0 && nx < self.width && ny >= 0 && ny < self.height && self.get((nx, ny)) == Tile::OccupiedSeat {
-                    res += 1;
-                }
-            }
-        }
+impl Seating {
+    /// The seating of the given layout, splitting the cells of each generation over `threads` threads
+    pub fn new(input: &str, rules: Rules, boundary: Boundary, threads: usize) -> Result<Self> {
+        let grid = parse_grid(&input::lines(input))?;
+        let automaton = match rules {
+            Rules::Adjacent => Automaton::with_boundary(grid, Neighbourhood::Moore, boundary, is_occupied, Box::new(seating_rule(4)) as Box<dyn Rule<Tile>>),
+            Rules::Visible => {
+                let neighbourhood = Neighbourhood::LineOfSight { transparent: |tile| *tile == Tile::Empty };
+                Automaton::with_boundary(grid, neighbourhood, boundary, is_occupied, Box::new(seating_rule(5)) as Box<dyn Rule<Tile>>)
+            },
+        };
+        Ok(Self { automaton: automaton.with_threads(threads) })
+    }
 
-        res
+    /// Computes the next generation, returning whether any seat changed
+    pub fn step(&mut self) -> bool {
+        self.automaton.step()
     }
 
-    fn count_occupied_distant(&self, (x, y): (i32, i32)) -> usize {
-        let mut res = 0;
-        for dx in -1..=1 {
-            for dy in -1..=1 {
-                if (dx, dy) == (0, 0) {
-                    continue;
-                }
+    /// Number of steps done so far
+    pub fn generation(&self) -> usize {
+        self.automaton.generation()
+    }
 
-                if let Some(distant_seat) = self.occupied_seat_map.get(&((x, y), (dx, dy))) {
-                    if self.get(*distant_seat) == Tile::OccupiedSeat {
-                        res += 1;
-                    }
-                }
-            }
-        }
+    pub fn occupied(&self) -> usize {
+        count_occupied(self.automaton.grid())
+    }
 
-        res
+    /// Steps until the seating is stable or cyclic, returning how it ended and the number of occupied seats at that point
+    pub fn run(self) -> (Outcome, usize) {
+        run(self.automaton)
     }
+}
 
-    fn get(&self, (x, y): (i32, i32)) -> Tile {
-        self.tiles[y as usize][x as usize].clone()
+/// Runs the seating of the given layout until it is stable or cyclic, splitting the cells of each generation over `threads` threads.
+/// Returns how the seating ended and the number of occupied seats at that point.
+pub fn simulate(input: &str, rules: Rules, boundary: Boundary, threads: usize) -> Result<(Outcome, usize)> {
+    Ok(Seating::new(input, rules, boundary, threads)?.run())
+}
+
+fn count_occupied(grid: &Grid<Tile>) -> usize {
+    grid.count(is_occupied)
+}
+
+impl Solver for Solution {
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(input: &str) -> Result<Self> {
+        let lines = input::lines(input);
+
+        Ok(Self {
+            grid: parse_grid(&lines)?,
+            lines: lines.iter().map(|line| line.to_string()).collect(),
+        })
     }
 
-    fn set(&mut self, (x, y): (i32, i32), value: Tile) {
-        self.tiles[y as usize][x as usize] = value;
+    fn part1(&self) -> Result<usize> {
+        settle(adjacent_seating(self.grid.clone(), Boundary::Bounded))
     }
 
-    fn evolve(&self, first_part: bool) -> Self {
-        let mut new_map = self.clone();
-        let occupied_threshold = if first_part { 4 } else { 5 };
+    fn part2(&self) -> Result<usize> {
+        settle(visible_seating(self.grid.clone(), Boundary::Bounded))
+    }
+}
 
-        for seat in &self.seat_positions {
-            let count = if first_part {
-                self.count_occupied_adjacent(*seat)
-            } else {
-                self.count_occupied_distant(*seat)
-            };
+/// Shows every generation of the seating of the requested parts, then how long they took to stabilise
+pub fn visualize(input: &str, options: &Visualization) -> Result<()> {
+    let solution = Solution::parse(input)?;
 
-            if self.get(*seat) == Tile::Seat && count == 0 {
-                new_map.set(*seat, Tile::OccupiedSeat);
-            } else if self.get(*seat) == Tile::OccupiedSeat && count >= occupied_threshold {
-                new_map.set(*seat, Tile::Seat);
-            }
+    if let Some(export) = &options.export {
+        if options.part != Some(Part::Two) {
+            export_images("part1", adjacent_seating(solution.grid.clone(), options.boundary), export)?;
+        }
+        if options.part != Some(Part::One) {
+            export_images("part2", visible_seating(solution.grid.clone(), options.boundary), export)?;
         }
+        return Ok(());
+    }
 
-        new_map
+    let mut summaries = vec![];
+    if options.part != Some(Part::Two) {
+        summaries.push(animate("Part 1", adjacent_seating(solution.grid.clone(), options.boundary), options.delay)?);
+    }
+    if options.part != Some(Part::One) {
+        summaries.push(animate("Part 2", visible_seating(solution.grid.clone(), options.boundary), options.delay)?);
     }
 
-    fn count_all_occupied(&self) -> i32 {
-        let mut res = 0;
-        for seat in &self.seat_positions {
-            if self.get(*seat) == Tile::OccupiedSeat {
-                res += 1;
-            }
-        }
+    for summary in summaries {
+        println!("{}", summary);
+    }
+    Ok(())
+}
 
-        res
+fn animate<R: Rule<Tile>>(title: &str, mut seating: Automaton<Tile, R>, delay: Duration) -> Result<String> {
+    let mut stdout = std::io::stdout().lock();
+
+    // Clear the screen once, then draw every frame over the previous one
+    write!(stdout, "\x1b[2J")?;
+    let mut draw = |seating: &Automaton<Tile, R>| -> std::io::Result<()> {
+        write!(stdout, "\x1b[H\x1b[0m{}, generation {}\n{}", title, seating.generation(), render(seating.grid()))?;
+        stdout.flush()?;
+        sleep(delay);
+        Ok(())
+    };
+
+    draw(&seating)?;
+    let mut error = None;
+    let outcome = seating.run_with(None, |seating| if error.is_none() {
+        error = draw(seating).err();
+    });
+    if let Some(e) = error {
+        return Err(e.into());
     }
+    println!();
 
+    Ok(format!("{}: {}, {} seats occupied", title, outcome, count_occupied(seating.grid())))
 }
 
-impl Solution {
-    pub fn init() -> Self {
-        let mut lines = Vec::new();
-        for line in read_to_string("inputs/day11.txt").unwrap().lines() {
-            lines.push(line.to_string());
-        }
+/// Writes the requested generation as an image, or every generation up to it, stopping once stable or cyclic
+fn export_images<R: Rule<Tile>>(name: &str, mut seating: Automaton<Tile, R>, export: &Export) -> Result<()> {
+    let save = |seating: &Automaton<Tile, R>, frame: Option<usize>| -> Result<PathBuf> {
+        let path = image_path(&export.path, name, frame);
+        RgbImage::from_grid(seating.grid(), export.scale, Tile::rgb).save(&path)?;
+        Ok(path)
+    };
+
+    if !export.frames {
+        let description = match export.generation {
+            Some(generation) => {
+                // Stepping by hand, as `run` stops as soon as it finds a cycle
+                while seating.generation() < generation && seating.step() {}
+                format!("generation {}", generation)
+            },
+            None => format!("the last generation ({})", seating.run(None)),
+        };
+        let path = save(&seating, None)?;
+        println!("{}: wrote {} to {}", name, description, path.display());
+        return Ok(());
+    }
 
-        Self {
-            map: Map::from(&lines),
-            lines,
+    let mut frames = vec![save(&seating, Some(0))?];
+    let mut error = None;
+    let outcome = seating.run_with(export.generation, |seating| if error.is_none() {
+        match save(seating, Some(seating.generation())) {
+            Ok(path) => frames.push(path),
+            Err(e) => error = Some(e),
         }
+    });
+    if let Some(e) = error {
+        return Err(e);
     }
 
-    fn part1(&mut self) -> i32 {
-        let mut map = self.map.clone();
-        loop {
-            let new_map = map.evolve(true);
-            if new_map == map {
-                break;
+    println!("{}: wrote {} frames from {} to {} ({})", name, frames.len(), frames[0].display(), frames.last().unwrap().display(), outcome);
+    Ok(())
+}
+
+/// `dir/seats.png` becomes `dir/seats-part1.png`, or `dir/seats-part1-0042.png` for the frame 42
+fn image_path(path: &Path, name: &str, frame: Option<usize>) -> PathBuf {
+    let stem = path.file_stem().map_or_else(String::new, |stem| stem.to_string_lossy().into_owned());
+    let mut file_name = format!("{}-{}", stem, name);
+    if let Some(frame) = frame {
+        file_name.push_str(&format!("-{:04}", frame));
+    }
+    if let Some(extension) = path.extension() {
+        file_name.push('.');
+        file_name.push_str(&extension.to_string_lossy());
+    }
+    path.with_file_name(file_name)
+}
+
+/// The grid with ANSI colours, resetting them at the end of each row
+fn render(grid: &Grid<Tile>) -> String {
+    let mut rendered = String::new();
+    for row in grid.cells().chunks(grid.width()) {
+        let mut colour = "";
+        for tile in row {
+            if tile.ansi_colour() != colour {
+                colour = tile.ansi_colour();
+                rendered.push_str(colour);
             }
-            map = new_map;
+            rendered.push(tile.as_char());
         }
+        rendered.push_str("\x1b[0m\n");
+    }
+    rendered
+}
 
-        map.count_all_occupied()
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+L.LL.LL.LL
+LLLLLLL.LL
+L.L.L..L..
+LLLL.LL.LL
+L.LL.LL.LL
+L.LLLLL.LL
+..L.L.....
+LLLLLLLLLL
+L.LLLLLL.L
+L.LLLLL.LL
+";
+
+    #[test]
+    fn part1_example() {
+        assert_eq!(Solution::parse(EXAMPLE).unwrap().part1().unwrap(), 37);
     }
 
-    fn part2(&mut self) -> i32 {
-        let mut map = self.map.clone();
+    #[test]
+    fn part2_example() {
+        assert_eq!(Solution::parse(EXAMPLE).unwrap().part2().unwrap(), 26);
+    }
 
-        loop {
-            let new_map = map.evolve(false);
-            if new_map == map {
-                break;
-            }
-            map = new_map;
-        }
+    #[test]
+    fn generations() {
+        let solution = Solution::parse(EXAMPLE).unwrap();
+        assert_eq!(adjacent_seating(solution.grid.clone(), Boundary::Bounded).run(None), Outcome::Stable { generation: 5 });
+        assert_eq!(visible_seating(solution.grid.clone(), Boundary::Bounded).run(None), Outcome::Stable { generation: 6 });
+    }
 
-        map.count_all_occupied()
+    #[test]
+    fn oscillating_seating() {
+        // Every seat has at least 4 neighbours, so they are all taken and left in turn
+        let solution = Solution::parse(".LL.\nLLLL\nLLLL\n.LL.\n").unwrap();
+        let error = solution.part1().err().unwrap();
+        assert_eq!(error.message, "the seating never settles, it enters a cycle of period 2 at generation 0");
     }
 
-    pub fn solve(&mut self) {
-        println!("========= DAY 11 ========");
-        print!("Solving part 1: ");
-        io::stdout().flush().unwrap();
+    #[test]
+    fn parallel_simulation() {
+        for rules in [Rules::Adjacent, Rules::Visible] {
+            let serial = simulate(EXAMPLE, rules, Boundary::Bounded, 1).unwrap();
+            assert_eq!(simulate(EXAMPLE, rules, Boundary::Bounded, 3).unwrap(), serial);
+        }
+        assert_eq!(simulate(EXAMPLE, Rules::Visible, Boundary::Bounded, 4).unwrap(), (Outcome::Stable { generation: 6 }, 26));
+    }
 
-        let start = std::time::Instant::now();
-        let part1 = self.part1();
-        let part1_time = start.elapsed();
-        println!("{:?} (took {:?})", part1, part1_time);
+    #[test]
+    fn image_paths() {
+        assert_eq!(image_path(Path::new("out/seats.png"), "part1", None), Path::new("out/seats-part1.png"));
+        assert_eq!(image_path(Path::new("seats.ppm"), "part2", Some(42)), Path::new("seats-part2-0042.ppm"));
+    }
 
-        print!("Solving part 2: ");
-        io::stdout().flush().unwrap();
-        let start = std::time::Instant::now();
-        let part2 = self.part2();
-        let part2_time = start.elapsed();
-        println!("{:?} (took {:?})", part2, part2_time);
-        println!();
+    #[test]
+    fn invalid_tile() {
+        let error = Solution::parse("L.L\nLxL\n").err().unwrap();
+        assert_eq!((error.line, error.column), (Some(2), Some(2)));
     }
-}
\ No newline at end of file
+}
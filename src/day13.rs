@@ -1,73 +1,63 @@
-use std::fs::read_to_string;
-use std::io::{self, Write};
-
 #[allow(unused_imports)]
 use itertools::Itertools;
 
+use crate::error::{Error, Result};
+use crate::input;
+use crate::number_theory::crt;
+use crate::solver::Solver;
+
 pub struct Solution {
     lines: Vec<String>,
 
     earliest_bus: i64,
     valid_bus_ids: Vec<i64>,
-    valid_bus_ids_with_indices: Vec<(i64, i64)>,
-}
-
-fn pos_mod(mut a: i64, b: i64) -> i64 {
-    while a < 0 {
-        a = a + b;
-    }
-
-    a %  b
-}
-
-fn inv_mod(a: i64, n: i64) -> i64 {
-    let mut t = 0;
-    let mut newt = 1;
-    let mut r = n;
-    let mut newr = a;
-
-    while newr != 0 {
-        let quotient = r / newr;
-        (t, newt) = (newt, t - quotient * newt);
-        (r, newr) = (newr, r - quotient * newr);
-    }
-
-    if r > 1 {
-        panic!("a = {} is not invertible mod {}", a, n);
-    } else if t < 0 {
-        t = t + n;
-    }
-
-    t
+    /// Bus `b` at index `i` leaves `i` minutes after the first one when `t = -i (mod b)`
+    departures: Vec<(i64, i64)>,
 }
 
-impl Solution {
-    pub fn init() -> Self {
-        let mut lines = Vec::new();
-        for line in read_to_string("inputs/day13.txt").unwrap().lines() {
-            lines.push(line.to_string());
+impl Solver for Solution {
+    type Answer1 = i64;
+    type Answer2 = i64;
+
+    fn parse(input: &str) -> Result<Self> {
+        let block = input::lines(input);
+        let (timestamp_line, buses_line) = block.iter().collect_tuple()
+            .ok_or_else(|| Error::new(format!("expected 2 lines, found {}", block.len())))?;
+
+        let earliest_bus: i64 = timestamp_line.parse()
+            .map_err(|_| Error::new(format!("invalid timestamp '{}'", timestamp_line)).at_line(block.line_number(0)))?;
+
+        // Out of service buses are kept as 0
+        let mut bus_ids = Vec::new();
+        let mut column = 1;
+        for s in buses_line.split(',') {
+            let bus_id = match s.parse::<i64>() {
+                _ if s == "x" => 0,
+                Ok(bus_id) if bus_id > 0 => bus_id,
+                _ => return Err(Error::new(format!("invalid bus id '{}'", s)).at_line(block.line_number(1)).at_column(column)),
+            };
+            bus_ids.push(bus_id);
+            column += s.len() + 1;
         }
+        let lines = block.iter().map(|line| line.to_string()).collect();
+
+        let valid_bus_ids: Vec<i64> = bus_ids.iter().copied().filter(|b| *b != 0).collect();
+        let departures: Vec<(i64, i64)> = bus_ids.iter()
+                                                 .enumerate()
+                                                 .filter(|(_i, b)| **b != 0)
+                                                 .map(|(i, b)| (-(i as i64), *b))
+                                                 .collect();
 
-        let earliest_bus: i64 = lines[0].parse().unwrap();
-        let valid_bus_ids: Vec<i64> = lines[1].split(',').filter_map(|s| s.parse().ok()).collect();
-        let valid_bus_ids_with_indices: Vec<(i64, i64)> = lines[1].split(',')
-                                                                  .enumerate()
-                                                                  .map(|(i, s)| (i as i64, s.parse().unwrap_or(0)))
-                                                                  .filter(|(_a, b)| b != &0)
-                                                                  .map(|(a, b)| (pos_mod(b-a, b) , b))
-                                                                  .sorted_by_key(|(_a, b)| *b)
-                                                                  .rev()
-                                                                  .collect();
-        Self {
+        Ok(Self {
             lines,
 
             earliest_bus,
             valid_bus_ids,
-            valid_bus_ids_with_indices,
-        }
+            departures,
+        })
     }
 
-    fn part1(&mut self) -> i64 {
+    fn part1(&self) -> Result<i64> {
         let mut closest_bus = 0;
         let mut wait_time = i64::MAX;
 
@@ -80,62 +70,52 @@ impl Solution {
             }
         }
 
-        closest_bus * wait_time
+        Ok(closest_bus * wait_time)
     }
 
-    fn part2(&mut self) -> i64 {
-        let a: Vec<_> = self.valid_bus_ids_with_indices.iter().map(|(a, _n)| *a as i64).collect();
-        let n: Vec<_> = self.valid_bus_ids_with_indices.iter().map(|(_a, n)| *n as i64).collect();
-        let n_mult = n.iter().fold(1, |acc, v| acc * v);
-
-        // Version 1
-        // let mut base = a[0];
-        // let mut incr = n[0];
-        // for i in 1..a.len() {
-        //     let mut candidate = base;
-        //     while candidate < n_mult {
-        //         if candidate % n[i] == a[i] {
-        //             base = candidate;
-        //             incr *= n[i];
-        //             break;
-        //         }
-        //         candidate += incr;
-        //     }
-        // }
-        
-        // base
-
-        // Version 2
-        let mut x = 0;
-        for i in 0..a.len() {
-            let ai = a[i];
-            let ni = n[i];
-            let yi = n_mult / ni;
-
-            // Find zi such that zi = yi^-1 mod ni <> zi yi = 1 mod ni
-            let zi = inv_mod(yi, ni);
-            x += ai * yi * zi;
-        }
+    fn part2(&self) -> Result<i64> {
+        let (timestamp, _period) = crt(&self.departures)
+            .map_err(|e| Error::new(format!("no timestamp fits the schedule, {}", e.message)))?;
+        Ok(timestamp)
+    }
+}
 
-        x % n_mult
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+939
+7,13,x,x,59,x,31,19
+";
+
+    #[test]
+    fn part1_example() {
+        assert_eq!(Solution::parse(EXAMPLE).unwrap().part1().unwrap(), 295);
+    }
+
+    #[test]
+    fn part2_examples() {
+        assert_eq!(Solution::parse(EXAMPLE).unwrap().part2().unwrap(), 1068781);
+
+        for (buses, expected) in [
+            ("17,x,13,19", 3417),
+            ("67,7,59,61", 754018),
+            ("67,x,7,59,61", 779210),
+            ("67,7,x,59,61", 1261476),
+            ("1789,37,47,1889", 1202161486),
+        ] {
+            let input = format!("0\n{}\n", buses);
+            assert_eq!(Solution::parse(&input).unwrap().part2().unwrap(), expected, "{}", buses);
+        }
     }
 
-    pub fn solve(&mut self) {
-        println!("========= DAY 13 ========");
-        print!("Solving part 1: ");
-        io::stdout().flush().unwrap();
-
-        let start = std::time::Instant::now();
-        let part1 = self.part1();
-        let part1_time = start.elapsed();
-        println!("{:?} (took {:?})", part1, part1_time);
-
-        print!("Solving part 2: ");
-        io::stdout().flush().unwrap();
-        let start = std::time::Instant::now();
-        let part2 = self.part2();
-        let part2_time = start.elapsed();
-        println!("{:?} (took {:?})", part2, part2_time);
-        println!();
+    #[test]
+    fn impossible_schedule() {
+        // t = 0 (mod 7) and t = -2 (mod 14) cannot both hold, which only matters to part 2
+        let solution = Solution::parse("0\n7,x,14\n").unwrap();
+        assert_eq!(solution.part1().unwrap(), 49);
+        let error = solution.part2().err().unwrap();
+        assert_eq!(error.message, "no timestamp fits the schedule, x = -2 (mod 14) contradicts the previous congruences");
     }
-}
\ No newline at end of file
+}
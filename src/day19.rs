@@ -1,119 +1,118 @@
-use std::fs::read_to_string;
-use std::io::{self, Write};
 use std::collections::HashMap;
 
 #[allow(unused_imports)]
 use itertools::Itertools;
 
+use crate::error::{Error, Result};
+use crate::input::{self, Block};
+use crate::solver::Solver;
+
 pub struct Solution {
     rules: HashMap<usize, Rule>,
     messages: Vec<String>,
 }
 
-impl Solution {
-    pub fn init() -> Self {
-        let content = read_to_string("inputs/day19.txt").unwrap();
+impl Solver for Solution {
+    type Answer1 = usize;
+    type Answer2 = usize;
 
+    fn parse(input: &str) -> Result<Self> {
         // Parse 
-        let (rules_lines, messages_lines) = content.split("\n\n").collect_tuple().unwrap();
-        let rules = parse_rules(&rules_lines.lines().collect_vec());
-        let messages = messages_lines.lines().map(|x| x.to_string()).collect_vec();
+        let paragraphs = input::paragraphs(input);
+        let num_paragraphs = paragraphs.len();
+        let (rules_lines, messages_lines) = paragraphs.into_iter().collect_tuple()
+            .ok_or_else(|| Error::new(format!("expected the rules and the messages, found {} paragraphs", num_paragraphs)))?;
+        let rules = parse_rules(&rules_lines)?;
+        let messages = messages_lines.iter().map(|x| x.to_string()).collect_vec();
 
 
-        Self {
+        Ok(Self {
             rules,
             messages,
-        }
+        })
     }
 
-    fn _check_rule(&self, rule: &Rule, x: &str, i: usize, r: usize) -> Vec<usize> {
-        let mut res = vec![];
-        match rule {
-            Rule::Char(c) => {
-                if i < x.len() && x.chars().nth(i).unwrap() == *c {
-                    res.push(i + 1);
-                }
-            },
-            Rule::Seq(seq) => {
-                if r == seq.len() {
-                    res.push(i);
-                } else {
-                    for j in self._check_rule(self.rules.get(&seq[r]).unwrap(), x, i, 0) {
-                        res.extend(self._check_rule(rule, x, j, r + 1));
-                    }
-                }
-            },
-            Rule::Or(rules) => {
-                for rule in rules {
-                    res.extend(self._check_rule(rule, x, i, 0))
-                }
-            }
-        }
+    fn part1(&self) -> Result<usize> {
+        Ok(self.messages.iter().filter(|x| check_rule(&self.rules, x)).count())
+    }
 
-        res
+    fn part2(&self) -> Result<usize> {
+        // Replace the rules 8 and 11 with their looping versions, leaving the parsed rules untouched
+        let mut rules = self.rules.clone();
+        rules.insert( 8, Rule::Or(vec![Rule::Seq(vec![42]), Rule::Seq(vec![42, 8])]));
+        rules.insert(11, Rule::Or(vec![Rule::Seq(vec![42, 31]), Rule::Seq(vec![42, 11, 31])]));
+        Ok(self.messages.iter().filter(|x| check_rule(&rules, x)).count())
     }
+}
 
-    fn check_rule(&self, x: &str) -> bool {
-        let initial_rule = self.rules.get(&0).unwrap();
-        for i in self._check_rule(initial_rule, x, 0, 0) {
-            if i == x.len() {
-                return true;
+fn _check_rule(rules: &HashMap<usize, Rule>, rule: &Rule, x: &str, i: usize, r: usize) -> Vec<usize> {
+    let mut res = vec![];
+    match rule {
+        Rule::Char(c) => {
+            if i < x.len() && x.chars().nth(i).unwrap() == *c {
+                res.push(i + 1);
+            }
+        },
+        Rule::Seq(seq) => {
+            if r == seq.len() {
+                res.push(i);
+            } else {
+                for j in _check_rule(rules, rules.get(&seq[r]).unwrap(), x, i, 0) {
+                    res.extend(_check_rule(rules, rule, x, j, r + 1));
+                }
+            }
+        },
+        Rule::Or(alternatives) => {
+            for rule in alternatives {
+                res.extend(_check_rule(rules, rule, x, i, 0))
             }
         }
-        false
     }
 
-    fn part1(&mut self) -> usize {
-        self.messages.iter().filter(|x| self.check_rule(x)).count()
-    }
-
-    fn part2(&mut self) -> usize {
-        self.rules.insert( 8, Rule::Or(vec![Rule::Seq(vec![42]), Rule::Seq(vec![42, 8])]));
-        self.rules.insert(11, Rule::Or(vec![Rule::Seq(vec![42, 31]), Rule::Seq(vec![42, 11, 31])]));
-        self.messages.iter().filter(|x| self.check_rule(x)).count()
-    }
+    res
+}
 
-    pub fn solve(&mut self) {
-        println!("========= DAY 19 ========");
-        print!("Solving part 1: ");
-        io::stdout().flush().unwrap();
-
-        let start = std::time::Instant::now();
-        let part1 = self.part1();
-        let part1_time = start.elapsed();
-        println!("{:?} (took {:?})", part1, part1_time);
-
-        print!("Solving part 2: ");
-        io::stdout().flush().unwrap();
-        let start = std::time::Instant::now();
-        let part2 = self.part2();
-        let part2_time = start.elapsed();
-        println!("{:?} (took {:?})", part2, part2_time);
-        println!();
+fn check_rule(rules: &HashMap<usize, Rule>, x: &str) -> bool {
+    let initial_rule = rules.get(&0).unwrap();
+    for i in _check_rule(rules, initial_rule, x, 0, 0) {
+        if i == x.len() {
+            return true;
+        }
     }
+    false
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 enum Rule {
     Char(char),
     Seq(Vec<usize>), 
     Or(Vec<Rule>),
 }
 
-fn parse_rules(lines: &[&str]) -> HashMap<usize, Rule> {
+fn parse_rules(lines: &Block) -> Result<HashMap<usize, Rule>> {
     let mut rules = HashMap::new();
+    let mut references = vec![];
+    let mut line_of_rule = HashMap::new();
+
+    for (i, rule) in lines.iter().enumerate() {
+        let error = |message: String| Error::new(message).at_line(lines.line_number(i));
 
-    for rule in lines.iter() {
         // Find the rule ID 
-        let (id_str, rule_str) = rule.split(':').collect_tuple().unwrap();
-        let id = id_str.parse::<usize>().unwrap();
+        let (id_str, rule_str) = rule.split(':').collect_tuple()
+            .ok_or_else(|| error(format!("invalid rule '{}'", rule)))?;
+        let id = id_str.parse::<usize>()
+            .map_err(|_| error(format!("invalid rule id '{}'", id_str)))?;
+        line_of_rule.insert(id, i);
 
         // Parse the rule
         if !rule_str.contains('"') {
             // Seq rule
             let mut seq_rules = vec![];
             for seq in rule_str.split('|') {
-                let seq = seq.trim().split_whitespace().map(|x| x.parse::<usize>().unwrap()).collect_vec();
+                let seq = seq.split_whitespace()
+                    .map(|x| x.parse::<usize>().map_err(|_| error(format!("invalid rule reference '{}'", x))))
+                    .collect::<Result<Vec<_>>>()?;
+                references.extend(seq.iter().map(|reference| (i, *reference)));
                 seq_rules.push(Rule::Seq(seq));
             }
             rules.insert(id, Rule::Or(seq_rules));
@@ -123,19 +122,149 @@ fn parse_rules(lines: &[&str]) -> HashMap<usize, Rule> {
             let c = rule_str
                 .trim()
                 .strip_prefix('"')
-                .unwrap()
-                .strip_suffix('"')
-                .unwrap()
-                .chars()
-                .next()
-                .unwrap();
+                .and_then(|s| s.strip_suffix('"'))
+                .and_then(|s| s.chars().exactly_one().ok())
+                .ok_or_else(|| error(format!("invalid character rule '{}'", rule_str.trim())))?;
             rules.insert(id, Rule::Char(c));
         }
     }
 
-    rules
+    // Make sure every referenced rule exists
+    if !rules.contains_key(&0) {
+        return Err(Error::new("missing rule 0"));
+    }
+    for (i, reference) in references {
+        if !rules.contains_key(&reference) {
+            return Err(Error::new(format!("reference to undefined rule {}", reference)).at_line(lines.line_number(i)));
+        }
+    }
+
+    // Part 2 replaces the rules 8 and 11 with looping versions referencing the rules 42 and 31
+    let mut reachable = vec![0];
+    let mut to_visit = vec![0];
+    while let Some(id) = to_visit.pop() {
+        if let Rule::Or(alternatives) = &rules[&id] {
+            for reference in alternatives.iter().flat_map(|seq| match seq {
+                Rule::Seq(seq) => seq.as_slice(),
+                _ => &[],
+            }) {
+                if !reachable.contains(reference) {
+                    reachable.push(*reference);
+                    to_visit.push(*reference);
+                }
+            }
+        }
+    }
+    for looping in [8, 11] {
+        if let Some(missing) = reachable.contains(&looping).then(|| [42, 31].into_iter().find(|id| !rules.contains_key(id))).flatten() {
+            return Err(Error::new(format!("rule {} needs rule {} once replaced in part 2", looping, missing)).at_line(lines.line_number(line_of_rule[&looping])));
+        }
+    }
+
+    Ok(rules)
 }
 
 // fn match_rules(message: &str, rules: &HashMap<usize, Rule>) -> bool {
 
-// }
\ No newline at end of file
+// }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+0: 4 1 5
+1: 2 3 | 3 2
+2: 4 4 | 5 5
+3: 4 5 | 5 4
+4: \"a\"
+5: \"b\"
+
+ababbb
+bababa
+abbbab
+aaabbb
+aaaabbb
+";
+
+    const LOOPING_EXAMPLE: &str = "\
+42: 9 14 | 10 1
+9: 14 27 | 1 26
+10: 23 14 | 28 1
+1: \"a\"
+11: 42 31
+5: 1 14 | 15 1
+19: 14 1 | 14 14
+12: 24 14 | 19 1
+16: 15 1 | 14 14
+31: 14 17 | 1 13
+6: 14 14 | 1 14
+2: 1 24 | 14 4
+0: 8 11
+13: 14 3 | 1 12
+15: 1 | 14
+17: 14 2 | 1 7
+23: 25 1 | 22 14
+28: 16 1
+4: 1 1
+20: 14 14 | 1 15
+3: 5 14 | 16 1
+27: 1 6 | 14 18
+14: \"b\"
+21: 14 1 | 1 14
+25: 1 1 | 1 14
+22: 14 14
+8: 42
+26: 14 22 | 1 20
+18: 15 15
+7: 14 5 | 1 21
+24: 14 1
+
+abbbbbabbbaaaababbaabbbbabababbbabbbbbbabaaaa
+bbabbbbaabaabba
+babbbbaabbbbbabbbbbbaabaaabaaa
+aaabbbbbbaaaabaababaabababbabaaabbababababaaa
+bbbbbbbaaaabbbbaaabbabaaa
+bbbababbbbaaaaaaaabbababaaababaabab
+ababaaaaaabaaab
+ababaaaaabbbaba
+baabbaaaabbaaaababbaababb
+abbbbabbbbaaaababbbbbbaaaababb
+aaaaabbaabaaaaababaa
+aaaabbaaaabbaaa
+aaaabbaabbaaaaaaabbbabbbaaabbaabaaa
+babaaabbbaaabaababbaabababaaab
+aabbbbbaabbbaaaaaabbbbbababaaaaabbaaabba
+";
+
+    #[test]
+    fn part1_examples() {
+        assert_eq!(Solution::parse(EXAMPLE).unwrap().part1().unwrap(), 2);
+        assert_eq!(Solution::parse(LOOPING_EXAMPLE).unwrap().part1().unwrap(), 3);
+    }
+
+    #[test]
+    fn part2_example() {
+        assert_eq!(Solution::parse(LOOPING_EXAMPLE).unwrap().part2().unwrap(), 12);
+    }
+
+    #[test]
+    fn parts_are_independent() {
+        let solution = Solution::parse(LOOPING_EXAMPLE).unwrap();
+        assert_eq!(solution.part2().unwrap(), 12);
+        assert_eq!(solution.part1().unwrap(), 3);
+    }
+
+    #[test]
+    fn looping_rules_need_42_and_31() {
+        let error = Solution::parse("0: 8 11\n8: 1\n11: 1\n1: \"a\"\n\naa\n").err().unwrap();
+        assert_eq!(error.line, Some(2));
+        assert!(Solution::parse("0: 1\n8: 1\n1: \"a\"\n\na\n").is_ok());
+    }
+
+    #[test]
+    fn undefined_rule() {
+        let error = Solution::parse("0: 1 2\n1: \"a\"\n\na\n").err().unwrap();
+        assert_eq!(error.line, Some(1));
+    }
+}
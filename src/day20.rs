@@ -1,36 +1,32 @@
 use std::collections::{HashMap, HashSet};
 use std::fmt::{Debug, Display};
-use std::fs::read_to_string;
-use std::io::{self, Write};
 
 #[allow(unused_imports)]
 use itertools::Itertools;
 
+use crate::error::{Error, Result};
+use crate::input::{self, Block};
+use crate::solver::Solver;
+
 pub struct Solution {
-    content: String,
-    tiles: Option<HashMap<usize, Image>>,
-    corners: Option<Vec<usize>>,
+    tiles: HashMap<usize, Image>,
+    corners: Vec<usize>,
 }
 
 const TILE_SIZE: usize = 10;
 
-impl Solution {
-    pub fn init() -> Self {
-        let content = read_to_string("inputs/day20.txt").unwrap();
+impl Solver for Solution {
+    type Answer1 = usize;
+    type Answer2 = usize;
 
-        Self {
-            content,
-            tiles: None,
-            corners: None,
-        }
-    }
-
-    fn part1(&mut self) -> usize {
+    fn parse(input: &str) -> Result<Self> {
         // Parse the input into tiles
         let mut tiles = HashMap::new();
-        for block in self.content.split("\r\n\r\n") {
-            let (id, tile) = Image::parse(&block.lines().collect::<Vec<_>>());
-            tiles.insert(id, tile);
+        for block in input::paragraphs(input) {
+            let (id, tile) = Image::parse(&block)?;
+            if tiles.insert(id, tile).is_some() {
+                return Err(Error::new(format!("duplicate tile {}", id)).at_line(block.line_number(0)));
+            }
         }
 
         // Find the adjacents for each tile
@@ -44,18 +40,23 @@ impl Solution {
             .filter(|(_, tile)| tile.adjacents.len() == 2)
             .map(|(id, _)| *id)
             .collect();
-        let result = corners.iter().product::<usize>();
+        if corners.len() != 4 {
+            return Err(Error::new(format!("the tiles do not form a square image, found {} corner tiles", corners.len())));
+        }
 
-        // Save the results for part 2
-        self.tiles = Some(tiles);
-        self.corners = Some(corners);
+        Ok(Self {
+            tiles,
+            corners,
+        })
+    }
 
-        result
+    fn part1(&self) -> Result<usize> {
+        Ok(self.corners.iter().product::<usize>())
     }
 
-    fn part2(&mut self) -> usize {
-        let tiles = self.tiles.as_mut().unwrap();
-        let corners = self.corners.as_mut().unwrap();
+    fn part2(&self) -> Result<usize> {
+        let tiles = &self.tiles;
+        let corners = &self.corners;
 
         // Rebuild the image
         // 1) Pick a corner tile
@@ -138,14 +139,14 @@ impl Solution {
         // println!("{}", image);
         
         // 7) Setup the sea monster
-        let sea_monster = vec![
+        let sea_monster = [
             "..................#.",
             "#....##....##....###",
             ".#..#..#..#..#..#...",
-        ].iter().map(|line| line.chars().map(|c| Pixel::from(c)).collect::<Vec<_>>()).collect::<Vec<_>>();
+        ];
         let sea_monster_coordinates = sea_monster.iter().enumerate().flat_map(|(y, row)| {
-            row.iter().enumerate().filter_map(move |(x, pixel)| {
-                if *pixel == Pixel::Hash {
+            row.chars().enumerate().filter_map(move |(x, c)| {
+                if c == '#' {
                     Some((x as i32, y as i32))
                 } else {
                     None
@@ -156,44 +157,25 @@ impl Solution {
         // 8) Find the sea monsters without rotating the image
         for _ in 0..4 {
             if let Some(num_non_monster_tiles) = image.count_non_monster_tiles(&sea_monster_coordinates) {
-                return num_non_monster_tiles;
+                return Ok(num_non_monster_tiles);
             }
 
             let mut image_copy = image.clone();
             image_copy.flip_horizontal();
             if let Some(num_non_monster_tiles) = image_copy.count_non_monster_tiles(&sea_monster_coordinates) {
-                return num_non_monster_tiles;
+                return Ok(num_non_monster_tiles);
             }
 
             image_copy = image.clone();
             image_copy.flip_vertical();
             if let Some(num_non_monster_tiles) = image_copy.count_non_monster_tiles(&sea_monster_coordinates) {
-                return num_non_monster_tiles;
+                return Ok(num_non_monster_tiles);
             }
 
             image.rotate();
         }
 
-        0
-    }
-
-    pub fn solve(&mut self) {
-        println!("========= DAY 20 ========");
-        print!("Solving part 1: ");
-        io::stdout().flush().unwrap();
-
-        let start = std::time::Instant::now();
-        let part1 = self.part1();
-        let part1_time = start.elapsed();
-        println!("{:?} (took {:?})", part1, part1_time);
-
-        print!("Solving part 2: ");
-        io::stdout().flush().unwrap();
-        let start = std::time::Instant::now();
-        let part2 = self.part2();
-        let part2_time = start.elapsed();
-        println!("{:?} (took {:?})", part2, part2_time);
-        println!();
+        Ok(0)
     }
 }
 
@@ -222,7 +204,7 @@ impl Direction {
         }
     }
 
-    fn to_delta(&self) -> (i32, i32) {
+    fn to_delta(self) -> (i32, i32) {
         match self {
             Direction::Top => (0, -1),
             Direction::Right => (1, 0),
@@ -241,12 +223,14 @@ impl Display for Pixel {
     }
 }
 
-impl From<char> for Pixel {
-    fn from(c: char) -> Self {
+impl TryFrom<char> for Pixel {
+    type Error = Error;
+
+    fn try_from(c: char) -> Result<Self> {
         match c {
-            '.' => Pixel::Dot,
-            '#' => Pixel::Hash,
-            _ => panic!("Invalid tile character {}", c),
+            '.' => Ok(Pixel::Dot),
+            '#' => Ok(Pixel::Hash),
+            _ => Err(Error::new(format!("invalid tile character '{}'", c))),
         }
     }
 }
@@ -260,21 +244,27 @@ struct Image {
 }
 
 impl Image {
-    fn parse(lines: &[&str]) -> (usize, Self) {
+    fn parse(lines: &Block) -> Result<(usize, Self)> {
         // Parse the tile ID 
         let tile_id = lines[0]
             .strip_prefix("Tile ")
-            .unwrap()
-            .strip_suffix(':')
-            .unwrap()
-            .parse::<usize>()
-            .unwrap();
+            .and_then(|s| s.strip_suffix(':'))
+            .and_then(|s| s.parse::<usize>().ok())
+            .ok_or_else(|| Error::new(format!("invalid tile header '{}'", lines[0])).at_line(lines.line_number(0)))?;
+
+        if lines.len() != TILE_SIZE + 1 {
+            return Err(Error::new(format!("tile {} has {} rows instead of {}", tile_id, lines.len() - 1, TILE_SIZE)).at_line(lines.line_number(0)));
+        }
 
         let mut contents = Vec::with_capacity(TILE_SIZE);
-        for line in lines[1..].iter() {
+        for (i, line) in lines.iter().enumerate().skip(1) {
+            if line.len() != TILE_SIZE {
+                return Err(Error::new(format!("tile {} has a row of {} pixels instead of {}", tile_id, line.len(), TILE_SIZE)).at_line(lines.line_number(i)));
+            }
+
             let mut row = Vec::with_capacity(TILE_SIZE);
-            for c in line.chars() {
-                row.push(Pixel::from(c));
+            for (j, c) in line.chars().enumerate() {
+                row.push(Pixel::try_from(c).map_err(|e| e.at_line(lines.line_number(i)).at_column(j + 1))?);
             }
             contents.push(row);
         }
@@ -282,12 +272,12 @@ impl Image {
         // Generate the borders
         let borders = Self::generate_borders(&contents);
 
-        (tile_id, Self {
+        Ok((tile_id, Self {
             id: tile_id,
             contents,
             borders,
             adjacents: vec![],
-        })
+        }))
     }
 
     fn raw(contents: Vec<Vec<Pixel>>) -> Self {
@@ -334,7 +324,7 @@ impl Image {
         }
     }
 
-    fn generate_borders(contents: &Vec<Vec<Pixel>>) -> HashMap<Vec<Pixel>, (Direction, bool)> {
+    fn generate_borders(contents: &[Vec<Pixel>]) -> HashMap<Vec<Pixel>, (Direction, bool)> {
         let mut borders = HashMap::new();
         
         // The order of the items in the border must be so that only by rotating the tile we can get a "direct" border to match
@@ -376,7 +366,7 @@ impl Image {
         None
     }
 
-    fn identify_border(&self, border: &Vec<Pixel>) -> (Direction, bool) {
+    fn identify_border(&self, border: &[Pixel]) -> (Direction, bool) {
         *self.borders.get(border).unwrap()
     }
 
@@ -443,4 +433,136 @@ impl Debug for Image {
         }
         Ok(())
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+Tile 2311:
+..##.#..#.
+##..#.....
+#...##..#.
+####.#...#
+##.##.###.
+##...#.###
+.#.#.#..##
+..#....#..
+###...#.#.
+..###..###
+
+Tile 1951:
+#.##...##.
+#.####...#
+.....#..##
+#...######
+.##.#....#
+.###.#####
+###.##.##.
+.###....#.
+..#.#..#.#
+#...##.#..
+
+Tile 1171:
+####...##.
+#..##.#..#
+##.#..#.#.
+.###.####.
+..###.####
+.##....##.
+.#...####.
+#.##.####.
+####..#...
+.....##...
+
+Tile 1427:
+###.##.#..
+.#..#.##..
+.#.##.#..#
+#.#.#.##.#
+....#...##
+...##..##.
+...#.#####
+.#.####.#.
+..#..###.#
+..##.#..#.
+
+Tile 1489:
+##.#.#....
+..##...#..
+.##..##...
+..#...#...
+#####...#.
+#..#.#.#.#
+...#.#.#..
+##.#...##.
+..##.##.##
+###.##.#..
+
+Tile 2473:
+#....####.
+#..#.##...
+#.##..#...
+######.#.#
+.#...#.#.#
+.#########
+.###.#..#.
+########.#
+##...##.#.
+..###.#.#.
+
+Tile 2971:
+..#.#....#
+#...###...
+#.#.###...
+##.##..#..
+.#####..##
+.#..####.#
+#..#.#..#.
+..####.###
+..#.#.###.
+...#.#.#.#
+
+Tile 2729:
+...#.#.#.#
+####.#....
+..#.#.....
+....#..#.#
+.##..##.#.
+.#.####...
+####.#.#..
+##.####...
+##..#.##..
+#.##...##.
+
+Tile 3079:
+#.#.#####.
+.#..######
+..#.......
+######....
+####.#..#.
+.#...#.##.
+#.#####.##
+..#.###...
+..#.......
+..#.###...
+";
+
+    #[test]
+    fn part1_example() {
+        assert_eq!(Solution::parse(EXAMPLE).unwrap().part1().unwrap(), 1951 * 3079 * 2971 * 1171);
+    }
+
+    #[test]
+    fn part2_example() {
+        assert_eq!(Solution::parse(EXAMPLE).unwrap().part2().unwrap(), 273);
+    }
+
+    #[test]
+    fn invalid_pixel() {
+        let input = EXAMPLE.replacen("..##.#..#.", "..##.#x.#.", 1);
+        let error = Solution::parse(&input).err().unwrap();
+        assert_eq!((error.line, error.column), (Some(2), Some(7)));
+    }
+}
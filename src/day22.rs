@@ -1,52 +1,66 @@
-use std::fs::read_to_string;
-use std::io::{self, Write};
 use std::collections::HashSet;
 
 #[allow(unused_imports)]
 use itertools::Itertools;
 
+use crate::error::{Error, Result};
+use crate::input::{self, Block};
+use crate::solver::Solver;
+
 pub struct Solution {
-    contents: String,
+    decks: (Deck, Deck),
 }
 
-impl Solution {
-    pub fn init() -> Self {
+impl Solver for Solution {
+    type Answer1 = Card;
+    type Answer2 = Card;
+
+    fn parse(input: &str) -> Result<Self> {
+        let paragraphs = input::paragraphs(input);
+        let num_paragraphs = paragraphs.len();
+        let (player1_lines, player2_lines) = paragraphs.into_iter().collect_tuple()
+            .ok_or_else(|| Error::new(format!("expected the decks of 2 players, found {}", num_paragraphs)))?;
+
+        let decks = (Deck::parse(&player1_lines)?, Deck::parse(&player2_lines)?);
 
-        Self {
-            contents: read_to_string("inputs/day22.txt").unwrap(),
+        // Rounds need a winner, so a card cannot appear twice
+        let mut seen = HashSet::new();
+        for (lines, deck) in [(&player1_lines, &decks.0), (&player2_lines, &decks.1)] {
+            for (i, card) in deck.0.iter().enumerate() {
+                if !seen.insert(*card) {
+                    return Err(Error::new(format!("duplicate card {}", card)).at_line(lines.line_number(i + 1)));
+                }
+            }
         }
+
+        Ok(Self { decks })
     }
 
-    fn part1(&mut self) -> Card {
-        let (player1_str, player2_str) = self.contents.split_once("\r\n\r\n").unwrap();
-        let mut player1 = Deck::parse(player1_str);
-        let mut player2 = Deck::parse(player2_str);
+    fn part1(&self) -> Result<Card> {
+        let (mut player1, mut player2) = self.decks.clone();
         
         while !player1.has_lost() && !player2.has_lost() {
             let card1 = player1.draw();
             let card2 = player2.draw();
             if card1 > card2 {
                 player1.append((card1, card2));
-            } else if card2 > card1 {
-                player2.append((card2, card1));
             } else {
-                panic!("Equal cards ??");
+                player2.append((card2, card1));
             }
         }
 
-        player1.score() + player2.score()
+        Ok(player1.score() + player2.score())
     }
 
-    fn part2(&mut self) -> Card {
-        let (player1_str, player2_str) = self.contents.split_once("\r\n\r\n").unwrap();
-        let player1 = Deck::parse(player1_str);
-        let player2 = Deck::parse(player2_str);
-        let winning_decks = self.play_game((player1, player2), &mut HashSet::new());
+    fn part2(&self) -> Result<Card> {
+        let winning_decks = self.play_game(self.decks.clone(), &mut HashSet::new());
 
-        winning_decks.0.score() + winning_decks.1.score()
+        Ok(winning_decks.0.score() + winning_decks.1.score())
     }
+}
 
-    fn play_game(&mut self, mut players: (Deck, Deck), seen_states: &mut HashSet<(Deck, Deck)>) -> (Deck, Deck) {
+impl Solution {
+    fn play_game(&self, mut players: (Deck, Deck), seen_states: &mut HashSet<(Deck, Deck)>) -> (Deck, Deck) {
         while !players.0.has_lost() && !players.1.has_lost() {
             if seen_states.contains(&players) {
                 // Game ends in a win for player 1
@@ -92,25 +106,6 @@ impl Solution {
 
         players
     }
-
-    pub fn solve(&mut self) {
-        println!("========= DAY 22 ========");
-        print!("Solving part 1: ");
-        io::stdout().flush().unwrap();
-
-        let start = std::time::Instant::now();
-        let part1 = self.part1();
-        let part1_time = start.elapsed();
-        println!("{:?} (took {:?})", part1, part1_time);
-
-        print!("Solving part 2: ");
-        io::stdout().flush().unwrap();
-        let start = std::time::Instant::now();
-        let part2 = self.part2();
-        let part2_time = start.elapsed();
-        println!("{:?} (took {:?})", part2, part2_time);
-        println!();
-    }
 }
 
 type Card = usize;
@@ -119,8 +114,18 @@ type Card = usize;
 struct Deck(Vec<Card>);
 
 impl Deck {
-    fn parse(input: &str) -> Self {
-        Self(input.lines().skip(1).map(|line| line.parse().unwrap()).collect())
+    fn parse(lines: &Block) -> Result<Self> {
+        if !lines[0].starts_with("Player ") || !lines[0].ends_with(':') {
+            return Err(Error::new(format!("invalid player header '{}'", lines[0])).at_line(lines.line_number(0)));
+        }
+
+        let cards = lines.iter()
+            .enumerate()
+            .skip(1)
+            .map(|(i, line)| line.parse().map_err(|_| Error::new(format!("invalid card '{}'", line)).at_line(lines.line_number(i))))
+            .collect::<Result<_>>()?;
+
+        Ok(Self(cards))
     }
 
     fn draw(&mut self) -> Card {
@@ -155,4 +160,47 @@ impl Deck {
     fn len(&self) -> usize {
         self.0.len()
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+Player 1:
+9
+2
+6
+3
+1
+
+Player 2:
+5
+8
+4
+7
+10
+";
+
+    #[test]
+    fn part1_example() {
+        assert_eq!(Solution::parse(EXAMPLE).unwrap().part1().unwrap(), 306);
+    }
+
+    #[test]
+    fn part2_example() {
+        assert_eq!(Solution::parse(EXAMPLE).unwrap().part2().unwrap(), 291);
+    }
+
+    #[test]
+    fn crlf_input() {
+        let input = EXAMPLE.replace('\n', "\r\n");
+        assert_eq!(Solution::parse(&input).unwrap().part1().unwrap(), 306);
+    }
+
+    #[test]
+    fn duplicate_cards() {
+        let error = Solution::parse("Player 1:\n5\n3\n\nPlayer 2:\n5\n2\n").err().unwrap();
+        assert_eq!(error.line, Some(6));
+    }
+}
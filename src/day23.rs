@@ -1,42 +1,64 @@
-use std::fmt::Debug;
-use std::fs::read_to_string;
-use std::io::{self, Write};
 use std::collections::HashMap;
 
 #[allow(unused_imports)]
 use itertools::Itertools;
 
+use crate::error::{Error, Result};
+use crate::input;
+use crate::solver::Solver;
+
 pub struct Solution {
     lines: Vec<String>,
+    labels: Vec<T>,
 }
 
-impl Solution {
-    pub fn init() -> Self {
-        let mut lines = Vec::new();
-        for line in read_to_string("inputs/day23.txt").unwrap().lines() {
-            lines.push(line.to_string());
+impl Solver for Solution {
+    type Answer1 = String;
+    type Answer2 = usize;
+
+    fn parse(input: &str) -> Result<Self> {
+        let lines = input::lines(input);
+        if lines.len() != 1 {
+            return Err(Error::new(format!("expected a single line of cup labels, found {} lines", lines.len())));
         }
 
-        Self {
-            lines,
+        let mut labels = Vec::new();
+        for (i, c) in lines[0].chars().enumerate() {
+            let label = c.to_digit(10)
+                .ok_or_else(|| Error::new(format!("invalid cup label '{}'", c)).at_line(lines.line_number(0)).at_column(i + 1))?;
+            labels.push(label as T);
         }
+
+        // A move picks up 3 cups and needs a destination other than the current cup
+        if labels.len() < 5 {
+            return Err(Error::new(format!("expected at least 5 cups, found {}", labels.len())).at_line(lines.line_number(0)));
+        }
+
+        // The game relies on the labels being exactly 1..=n
+        if labels.iter().copied().sorted().ne(1..=labels.len()) {
+            return Err(Error::new(format!("cup labels must be the numbers 1 to {}, each once", labels.len())).at_line(lines.line_number(0)));
+        }
+
+        Ok(Self {
+            lines: lines.iter().map(|line| line.to_string()).collect(),
+            labels,
+        })
     }
 
-    fn part1(&mut self) -> Cups {
-        let values = self.lines[0].chars().map(|c| c.to_digit(10).unwrap() as usize).collect::<Vec<_>>();
-        let mut cups = Cups::from(values);
+    fn part1(&self) -> Result<String> {
+        let mut cups = Cups::from(self.labels.clone());
         
-        for _ in 0..10 {
+        for _ in 0..100 {
             cups.play_move();
         }
 
-        cups
+        Ok(cups.labels_after_one())
     }
 
-    fn part2(&mut self) -> usize {
-        let mut values = self.lines[0].chars().map(|c| c.to_digit(10).unwrap() as usize).collect::<Vec<_>>();
+    fn part2(&self) -> Result<usize> {
+        let mut values = self.labels.clone();
         values.reserve(1_000_000);
-        for i in 10..=1_000_000 {
+        for i in values.len() + 1..=1_000_000 {
             values.push(i);
         }
         let mut cups = Cups::from(values);
@@ -47,26 +69,7 @@ impl Solution {
 
         let a = cups.cups[&1];
         let b = cups.cups[&a];
-        a * b
-    }
-
-    pub fn solve(&mut self) {
-        println!("========= DAY 23 ========");
-        print!("Solving part 1: ");
-        io::stdout().flush().unwrap();
-
-        let start = std::time::Instant::now();
-        let part1 = self.part1();
-        let part1_time = start.elapsed();
-        println!("{:?} (took {:?})", part1, part1_time);
-
-        print!("Solving part 2: ");
-        io::stdout().flush().unwrap();
-        let start = std::time::Instant::now();
-        let part2 = self.part2();
-        let part2_time = start.elapsed();
-        println!("{:?} (took {:?})", part2, part2_time);
-        println!();
+        Ok(a * b)
     }
 }
 
@@ -95,10 +98,9 @@ impl Cups {
     fn play_move(&mut self) {
         // 1) Step one, pick up three cups
         let current = self.current;
-        let mut picked_up = Vec::new();
-        picked_up.push(self.cups[&current]);
-        picked_up.push(self.cups[&picked_up[0]]);
-        picked_up.push(self.cups[&picked_up[1]]);
+        let first = self.cups[&current];
+        let second = self.cups[&first];
+        let picked_up = [first, second, self.cups[&second]];
         let next_current = self.cups[&picked_up[2]];
 
         let mut destination = current;
@@ -123,17 +125,43 @@ impl Cups {
         
         self.current = next_current;
     }
-}
 
-impl Debug for Cups {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        // Print the first 10 cups
+    /// Labels of the cups after cup 1, going clockwise
+    fn labels_after_one(&self) -> String {
+        let mut labels = String::new();
         let mut current = self.cups[&1];
-        for _ in 0..8 {
-            write!(f, "{}", current)?;
+        while current != 1 {
+            labels.push_str(&current.to_string());
             current = self.cups[&current];
         }
 
-        Ok(())
+        labels
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "389125467\n";
+
+    #[test]
+    fn part1_example() {
+        assert_eq!(Solution::parse(EXAMPLE).unwrap().part1().unwrap(), "67384529");
+    }
+
+    #[test]
+    #[ignore = "plays 10 million moves, run with `cargo test --release -- --ignored`"]
+    fn part2_example() {
+        assert_eq!(Solution::parse(EXAMPLE).unwrap().part2().unwrap(), 149245887792);
+    }
+
+    #[test]
+    fn invalid_labels() {
+        assert!(Solution::parse("38912546\n").is_err());
+        assert!(Solution::parse("3891254a7\n").is_err());
+        assert!(Solution::parse("1\n").is_err());
+        assert!(Solution::parse("3124\n").is_err());
+        assert!(Solution::parse("25143\n").is_ok());
     }
 }
@@ -1,46 +1,43 @@
 use std::collections::{HashSet, HashMap};
-use std::fs::read_to_string;
-use std::io::{self, Write};
 
 #[allow(unused_imports)]
 use itertools::Itertools;
 
+use crate::error::{Error, Result};
+use crate::input;
+use crate::solver::Solver;
+
 pub struct Solution {
     lines: Vec<String>,
-    black_tiles: HashSet<(i32, i32)>,
+    directions: Vec<Vec<Direction>>,
 }
 
-impl Solution {
-    pub fn init() -> Self {
-        let mut lines = Vec::new();
-        for line in read_to_string("inputs/day24.txt").unwrap().lines() {
-            lines.push(line.to_string());
-        }
+impl Solver for Solution {
+    type Answer1 = usize;
+    type Answer2 = usize;
 
-        Self {
-            lines,
-            black_tiles: HashSet::new(),
+    fn parse(input: &str) -> Result<Self> {
+        let lines = input::lines(input);
+        let mut directions = Vec::new();
+        for (i, line) in lines.iter().enumerate() {
+            directions.push(parse_directions(line).map_err(|e| e.at_line(lines.line_number(i)))?);
         }
-    }
 
-    fn part1(&mut self) -> usize {
-        for line in &self.lines {
-            let directions = parse_directions(line);
-            let (x, y) = locate(&directions);
-            if self.black_tiles.contains(&(x, y)) {
-                self.black_tiles.remove(&(x, y));
-            } else {
-                self.black_tiles.insert((x, y));
-            }
-        }
+        Ok(Self {
+            lines: lines.iter().map(|line| line.to_string()).collect(),
+            directions,
+        })
+    }
 
-        self.black_tiles.len()
+    fn part1(&self) -> Result<usize> {
+        Ok(self.flip_tiles().len())
     }
 
-    fn part2(&mut self) -> usize{
+    fn part2(&self) -> Result<usize> {
+        let mut black_tiles = self.flip_tiles();
         for _ in 0..100 {
             // Count neighboring tiles
-            let tiles = self.black_tiles.clone();
+            let tiles = black_tiles.clone();
             let mut neighbors_counts: HashMap<(i32, i32), i32> = HashMap::from_iter(tiles.iter().map(|&tile| (tile, 0)));
             for (x, y) in &tiles {
                 for direction in &[
@@ -59,35 +56,33 @@ impl Solution {
 
             // Update black tiles
             for (tile, count) in neighbors_counts {
-                let black = self.black_tiles.contains(&tile);
+                let black = black_tiles.contains(&tile);
                 if black && (count == 0 || count > 2) {
-                    self.black_tiles.remove(&tile);
+                    black_tiles.remove(&tile);
                 } else if !black && count == 2 {
-                    self.black_tiles.insert(tile);
+                    black_tiles.insert(tile);
                 }
             }
         }
         
-        self.black_tiles.len()
+        Ok(black_tiles.len())
     }
+}
+
+impl Solution {
+    /// Flips the tile at the end of each line of directions, returning the black tiles
+    fn flip_tiles(&self) -> HashSet<(i32, i32)> {
+        let mut black_tiles = HashSet::new();
+        for directions in &self.directions {
+            let (x, y) = locate(directions);
+            if black_tiles.contains(&(x, y)) {
+                black_tiles.remove(&(x, y));
+            } else {
+                black_tiles.insert((x, y));
+            }
+        }
 
-    pub fn solve(&mut self) {
-        println!("========= DAY 24 ========");
-        print!("Solving part 1: ");
-        io::stdout().flush().unwrap();
-
-        let start = std::time::Instant::now();
-        let part1 = self.part1();
-        let part1_time = start.elapsed();
-        println!("{:?} (took {:?})", part1, part1_time);
-
-        print!("Solving part 2: ");
-        io::stdout().flush().unwrap();
-        let start = std::time::Instant::now();
-        let part2 = self.part2();
-        let part2_time = start.elapsed();
-        println!("{:?} (took {:?})", part2, part2_time);
-        println!();
+        black_tiles
     }
 }
 
@@ -105,7 +100,7 @@ enum Direction {
 impl TryFrom<&str> for Direction {
     type Error = ();
 
-    fn try_from(value: &str) -> Result<Self, Self::Error> {
+    fn try_from(value: &str) -> std::result::Result<Self, Self::Error> {
         match value {
             "e" => Ok(Direction::East),
             "se" => Ok(Direction::SouthEast),
@@ -133,20 +128,25 @@ impl Direction {
     }
 }
 
-fn parse_directions(line: &str) -> Vec<Direction> {
+fn parse_directions(line: &str) -> Result<Vec<Direction>> {
     let mut directions = vec![];
-    let mut chars = line.chars();
     let mut current_str = String::new();
 
-    while let Some(c) = chars.next() {
+    for (i, c) in line.chars().enumerate() {
         current_str.push(c);
-        if let Some(direction) = Direction::try_from(current_str.as_str()).ok() {
+        if let Ok(direction) = Direction::try_from(current_str.as_str()) {
             directions.push(direction);
             current_str.clear();
+        } else if current_str != "n" && current_str != "s" {
+            return Err(Error::new(format!("invalid direction '{}'", current_str)).at_column(i + 2 - current_str.len()));
         }
     }
 
-    directions
+    if !current_str.is_empty() {
+        return Err(Error::new(format!("incomplete direction '{}'", current_str)).at_column(line.len()));
+    }
+
+    Ok(directions)
 }
 
 fn locate(directions: &[Direction]) -> (i32, i32) {
@@ -160,4 +160,56 @@ fn locate(directions: &[Direction]) -> (i32, i32) {
     }
 
     (x, y)
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+sesenwnenenewseeswwswswwnenewsewsw
+neeenesenwnwwswnenewnwwsewnenwseswesw
+seswneswswsenwwnwse
+nwnwneseeswswnenewneswwnewseswneseene
+swweswneswnenwsewnwneneseenw
+eesenwseswswnenwswnwnwsewwnwsene
+sewnenenenesenwsewnenwwwse
+wenwwweseeeweswwwnwwe
+wsweesenenewnwwnwsenewsenwwsesesenwne
+neeswseenwwswnwswswnw
+nenwswwsewswnenenewsenwsenwnesesenew
+enewnwewneswsewnwswenweswnenwsenwsw
+sweneswneswneneenwnewenewwneswswnese
+swwesenesewenwneswnwwneseswwne
+enesenwswwswneneswsenwnewswseenwsese
+wnwnesenesenenwwnenwsewesewsesesew
+nenewswnwewswnenesenwnesewesw
+eneswnwswnwsenenwnwnwwseeswneewsenese
+neswnwewnwnwseenwseesewsenwsweewe
+wseweeenwnesenwwwswnew
+";
+
+    #[test]
+    fn part1_example() {
+        assert_eq!(Solution::parse(EXAMPLE).unwrap().part1().unwrap(), 10);
+    }
+
+    #[test]
+    fn part2_example() {
+        assert_eq!(Solution::parse(EXAMPLE).unwrap().part2().unwrap(), 2208);
+    }
+
+    #[test]
+    fn parts_are_independent() {
+        let solution = Solution::parse(EXAMPLE).unwrap();
+        assert_eq!(solution.part2().unwrap(), 2208);
+        assert_eq!(solution.part1().unwrap(), 10);
+        assert_eq!(solution.part2().unwrap(), 2208);
+    }
+
+    #[test]
+    fn invalid_direction() {
+        let error = Solution::parse("esew\nnwx\n").err().unwrap();
+        assert_eq!((error.line, error.column), (Some(2), Some(3)));
+    }
+}
@@ -0,0 +1,78 @@
+use std::path::PathBuf;
+use std::time::Duration;
+
+use crate::automaton::Boundary;
+use crate::error::Result;
+use crate::runner::{solve, DayResult};
+use crate::solver::Part;
+
+use crate::day10;
+use crate::day11;
+use crate::day13;
+use crate::day19;
+use crate::day20;
+use crate::day22;
+use crate::day23;
+use crate::day24;
+
+/// Solves the given day from its input, running either one part or both of them
+pub type Runner = fn(u8, &str, Option<Part>) -> Result<DayResult>;
+
+/// Every implemented day, in increasing order
+pub const DAYS: &[(u8, Runner)] = &[
+    (10, solve::<day10::Solution>),
+    (11, solve::<day11::Solution>),
+    (13, solve::<day13::Solution>),
+    (19, solve::<day19::Solution>),
+    (20, solve::<day20::Solution>),
+    (22, solve::<day22::Solution>),
+    (23, solve::<day23::Solution>),
+    (24, solve::<day24::Solution>),
+];
+
+/// Options of `--visualize`
+pub struct Visualization {
+    pub part: Option<Part>,
+    /// Delay between two frames
+    pub delay: Duration,
+    /// Edges of the grid, for the days simulated with an automaton
+    pub boundary: Boundary,
+    /// Images to write instead of animating the terminal
+    pub export: Option<Export>,
+}
+
+/// Images written by `--export`
+pub struct Export {
+    /// A `.png` or `.ppm` file, whose name gets a `-partN` suffix, followed by the generation when writing frames
+    pub path: PathBuf,
+    /// Write every generation instead of a single one
+    pub frames: bool,
+    /// The generation to write, defaults to the last one
+    pub generation: Option<usize>,
+    /// Width in pixels of a cell
+    pub scale: usize,
+}
+
+/// Animates the given day from its input in the terminal
+pub type Visualizer = fn(&str, &Visualization) -> Result<()>;
+
+/// Every day that can be animated with `--visualize`
+pub const VISUALIZERS: &[(u8, Visualizer)] = &[
+    (11, day11::visualize),
+];
+
+pub fn find(day: u8) -> Option<Runner> {
+    DAYS.iter().find(|(number, _)| *number == day).map(|(_, runner)| *runner)
+}
+
+pub fn is_implemented(day: u8) -> bool {
+    find(day).is_some()
+}
+
+pub fn find_visualizer(day: u8) -> Option<Visualizer> {
+    VISUALIZERS.iter().find(|(number, _)| *number == day).map(|(_, visualizer)| *visualizer)
+}
+
+pub fn latest() -> u8 {
+    DAYS.last().unwrap().0
+}
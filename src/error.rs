@@ -0,0 +1,69 @@
+use std::fmt::Display;
+
+/// An error raised while solving a day, located in its input when possible
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Error {
+    pub day: Option<u8>,
+    /// 1-based line of the input
+    pub line: Option<usize>,
+    /// 1-based column of the line
+    pub column: Option<usize>,
+    pub message: String,
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+impl Error {
+    pub fn new(message: impl Into<String>) -> Self {
+        Self {
+            day: None,
+            line: None,
+            column: None,
+            message: message.into(),
+        }
+    }
+
+    pub fn in_day(mut self, day: u8) -> Self {
+        self.day = Some(day);
+        self
+    }
+
+    pub fn at_line(mut self, line: usize) -> Self {
+        self.line = Some(line);
+        self
+    }
+
+    pub fn at_column(mut self, column: usize) -> Self {
+        self.column = Some(column);
+        self
+    }
+
+    /// Shifts the line by `offset`, used when the error was located in a sub-part of the input
+    pub fn offset_lines(mut self, offset: usize) -> Self {
+        self.line = self.line.map(|line| line + offset);
+        self
+    }
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let location = [("day", self.day.map(|day| day as usize)), ("line", self.line), ("column", self.column)]
+            .iter()
+            .filter_map(|(name, value)| value.map(|value| format!("{} {}", name, value)))
+            .collect::<Vec<_>>();
+
+        if location.is_empty() {
+            write!(f, "{}", self.message)
+        } else {
+            write!(f, "{}: {}", location.join(", "), self.message)
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<std::io::Error> for Error {
+    fn from(value: std::io::Error) -> Self {
+        Error::new(value.to_string())
+    }
+}
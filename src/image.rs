@@ -0,0 +1,122 @@
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+use crate::automaton::Grid;
+use crate::error::{Error, Result};
+
+pub type Rgb = [u8; 3];
+
+/// An image with 8 bits per colour channel, stored row by row
+pub struct RgbImage {
+    width: usize,
+    height: usize,
+    pixels: Vec<u8>,
+}
+
+impl RgbImage {
+    /// Draws every cell of the grid as a `scale`x`scale` block of its colour
+    pub fn from_grid<C>(grid: &Grid<C>, scale: usize, colour: impl Fn(&C) -> Rgb) -> Self {
+        assert!(scale >= 1, "cells must be at least one pixel wide");
+
+        let (width, height) = (grid.width() * scale, grid.height() * scale);
+        let mut pixels = Vec::with_capacity(width * height * 3);
+        for row in grid.cells().chunks(grid.width()) {
+            let mut line = Vec::with_capacity(width * 3);
+            for cell in row {
+                let rgb = colour(cell);
+                for _ in 0..scale {
+                    line.extend_from_slice(&rgb);
+                }
+            }
+            for _ in 0..scale {
+                pixels.extend_from_slice(&line);
+            }
+        }
+
+        Self { width, height, pixels }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Writes the image as a binary PPM (`P6`)
+    pub fn write_ppm(&self, mut writer: impl Write) -> std::io::Result<()> {
+        write!(writer, "P6\n{} {}\n255\n", self.width, self.height)?;
+        writer.write_all(&self.pixels)
+    }
+
+    pub fn write_png(&self, writer: impl Write) -> Result<()> {
+        let mut encoder = png::Encoder::new(writer, self.width as u32, self.height as u32);
+        encoder.set_color(png::ColorType::Rgb);
+        encoder.set_depth(png::BitDepth::Eight);
+
+        let mut writer = encoder.write_header().map_err(|e| Error::new(e.to_string()))?;
+        writer.write_image_data(&self.pixels).map_err(|e| Error::new(e.to_string()))
+    }
+
+    /// Saves the image as a PNG or a PPM file, depending on the extension of the path
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let in_file = |e: String| Error::new(format!("{}: {}", path.display(), e));
+        let format = ImageFormat::from_path(path).ok_or_else(|| in_file("unknown image format, expected a .png or .ppm file".to_string()))?;
+
+        let mut writer = BufWriter::new(File::create(path).map_err(|e| in_file(e.to_string()))?);
+        match format {
+            ImageFormat::Png => self.write_png(&mut writer).map_err(|e| in_file(e.message))?,
+            ImageFormat::Ppm => self.write_ppm(&mut writer).map_err(|e| in_file(e.to_string()))?,
+        }
+        writer.flush().map_err(|e| in_file(e.to_string()))
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ImageFormat {
+    Png,
+    Ppm,
+}
+
+impl ImageFormat {
+    pub fn from_path(path: &Path) -> Option<Self> {
+        match path.extension()?.to_str()?.to_ascii_lowercase().as_str() {
+            "png" => Some(ImageFormat::Png),
+            "ppm" => Some(ImageFormat::Ppm),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BLACK: Rgb = [0, 0, 0];
+    const WHITE: Rgb = [255, 255, 255];
+
+    #[test]
+    fn blocks() {
+        let grid = Grid::new(2, 1, vec![false, true]);
+        let image = RgbImage::from_grid(&grid, 2, |&on| if on { WHITE } else { BLACK });
+        assert_eq!((image.width(), image.height()), (4, 2));
+
+        let mut ppm = vec![];
+        image.write_ppm(&mut ppm).unwrap();
+        let row = [BLACK, BLACK, WHITE, WHITE].concat();
+        assert_eq!(ppm, [b"P6\n4 2\n255\n".as_slice(), &row, &row].concat());
+
+        let mut png = vec![];
+        image.write_png(&mut png).unwrap();
+        assert!(png.starts_with(b"\x89PNG\r\n\x1a\n"));
+    }
+
+    #[test]
+    fn formats() {
+        assert_eq!(ImageFormat::from_path(Path::new("out/seats.PNG")), Some(ImageFormat::Png));
+        assert_eq!(ImageFormat::from_path(Path::new("seats.ppm")), Some(ImageFormat::Ppm));
+        assert_eq!(ImageFormat::from_path(Path::new("seats")), None);
+    }
+}
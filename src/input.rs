@@ -0,0 +1,171 @@
+use std::env;
+use std::fs::read_to_string;
+use std::io::{self, Read};
+use std::ops::Deref;
+use std::path::PathBuf;
+
+/// Environment variable overriding the directory containing the `dayNN.txt` inputs
+pub const INPUT_DIR_VAR: &str = "ADVENT_INPUT_DIR";
+
+/// Where the puzzle input of a day is read from
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum InputSource {
+    /// `dayNN.txt` in the input directory (see [`input_dir`])
+    Default,
+    /// An explicit file
+    Path(PathBuf),
+    /// The standard input
+    Stdin,
+}
+
+impl From<&str> for InputSource {
+    fn from(value: &str) -> Self {
+        match value {
+            "-" => InputSource::Stdin,
+            path => InputSource::Path(PathBuf::from(path)),
+        }
+    }
+}
+
+impl InputSource {
+    /// The path the input of `day` is read from, `None` for the standard input
+    pub fn path(&self, day: u8) -> Option<PathBuf> {
+        match self {
+            InputSource::Default => Some(input_dir().join(format!("day{}.txt", day))),
+            InputSource::Path(path) => Some(path.clone()),
+            InputSource::Stdin => None,
+        }
+    }
+
+    pub fn read(&self, day: u8) -> io::Result<String> {
+        match self.path(day) {
+            Some(path) => read_to_string(&path).map_err(|e| io::Error::new(e.kind(), format!("{}: {}", path.display(), e))),
+            None => {
+                let mut input = String::new();
+                io::stdin().read_to_string(&mut input)?;
+                Ok(input)
+            }
+        }
+    }
+}
+
+/// The directory containing the default inputs: `$ADVENT_INPUT_DIR` if set, `inputs/` otherwise.
+/// Relative paths are resolved from the crate root, so the runners work from any working directory.
+pub fn input_dir() -> PathBuf {
+    let dir = env::var_os(INPUT_DIR_VAR)
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from("inputs"));
+
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join(dir)
+}
+
+/// Consecutive lines of the input, remembering where they start for error reporting
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Block<'a> {
+    /// Number of input lines before the first line of the block
+    pub start: usize,
+    pub lines: Vec<&'a str>,
+}
+
+impl<'a> Block<'a> {
+    /// 1-based line number in the input of the `i`-th line of the block
+    pub fn line_number(&self, i: usize) -> usize {
+        self.start + i + 1
+    }
+}
+
+impl<'a> Deref for Block<'a> {
+    type Target = [&'a str];
+
+    fn deref(&self) -> &Self::Target {
+        &self.lines
+    }
+}
+
+impl<'a> IntoIterator for Block<'a> {
+    type Item = &'a str;
+    type IntoIter = std::vec::IntoIter<&'a str>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.lines.into_iter()
+    }
+}
+
+/// Splits the input into lines, whatever the line endings (LF or CRLF).
+/// Trailing whitespace is removed from every line, as well as leading and trailing blank lines.
+pub fn lines(input: &str) -> Block<'_> {
+    let lines: Vec<_> = input.lines().map(str::trim_end).collect();
+    let start = lines.iter().position(|line| !line.is_empty()).unwrap_or(lines.len());
+    let end = lines.iter().rposition(|line| !line.is_empty()).map_or(start, |i| i + 1);
+
+    Block {
+        start,
+        lines: lines[start..end].to_vec(),
+    }
+}
+
+/// Splits the input into paragraphs, ie blocks of lines separated by one or more blank lines.
+/// Lines are normalised the same way as [`lines`].
+pub fn paragraphs(input: &str) -> Vec<Block<'_>> {
+    let lines = lines(input);
+    let mut paragraphs = vec![];
+    let mut current = Block { start: lines.start, lines: vec![] };
+
+    for (i, line) in lines.iter().enumerate() {
+        if line.is_empty() {
+            if !current.lines.is_empty() {
+                paragraphs.push(current);
+            }
+            current = Block { start: lines.line_number(i), lines: vec![] };
+        } else {
+            current.lines.push(line);
+        }
+    }
+
+    if !current.lines.is_empty() {
+        paragraphs.push(current);
+    }
+
+    paragraphs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn crlf_and_trailing_whitespace() {
+        let block = lines("ab \r\ncd\t\r\n\r\nef\r\n");
+        assert_eq!(block.lines, ["ab", "cd", "", "ef"]);
+        assert_eq!(block.line_number(0), 1);
+        assert_eq!(block.line_number(3), 4);
+    }
+
+    #[test]
+    fn leading_and_trailing_blank_lines() {
+        let block = lines("\n  \r\nab\ncd\n\n \n");
+        assert_eq!(block.lines, ["ab", "cd"]);
+        assert_eq!(block.start, 2);
+        assert_eq!(block.line_number(1), 4);
+
+        let block = lines("\n \n");
+        assert!(block.is_empty());
+    }
+
+    #[test]
+    fn paragraphs_separated_by_blank_lines() {
+        let blocks = paragraphs("\r\nab\r\ncd \r\n\r\n  \r\n\r\nef\r\n\r\ngh\r\nij\r\n\r\n");
+        let lines: Vec<_> = blocks.iter().map(|block| block.lines.clone()).collect();
+        assert_eq!(lines, [vec!["ab", "cd"], vec!["ef"], vec!["gh", "ij"]]);
+
+        let first_lines: Vec<_> = blocks.iter().map(|block| block.line_number(0)).collect();
+        assert_eq!(first_lines, [2, 7, 9]);
+        assert_eq!(blocks[2].line_number(1), 10);
+    }
+
+    #[test]
+    fn no_paragraphs() {
+        assert!(paragraphs("").is_empty());
+        assert!(paragraphs("\r\n \n\n").is_empty());
+    }
+}
@@ -1,5 +1,19 @@
 #![allow(dead_code)]
 
+pub mod answer;
+pub mod answers;
+pub mod automaton;
+pub mod bench;
+pub mod days;
+pub mod error;
+pub mod image;
+pub mod input;
+pub mod number_theory;
+pub mod report;
+pub mod runner;
+pub mod scaffold;
+pub mod solver;
+
 pub mod day10;
 pub mod day11;
 pub mod day13;
@@ -8,4 +22,3 @@ pub mod day20;
 pub mod day22;
 pub mod day23;
 pub mod day24;
-pub mod day25;
@@ -1,16 +1,436 @@
-#![allow(dead_code)]
-#![allow(unused_imports)]
-
-use advent::day10;
-use advent::day11;
-use advent::day13;
-use advent::day19;
-use advent::day20;
-use advent::day22;
-use advent::day23;
-use advent::day24;
-use advent::day25;
-
-fn main() {
-    day25::Solution::init().solve();
-}
\ No newline at end of file
+use std::ops::RangeInclusive;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::time::Duration;
+
+use advent::answers::{self, Answers};
+use advent::automaton::Boundary;
+use advent::bench::{self, Baseline};
+use advent::days::{self, Export, Visualization, DAYS};
+use advent::error::Error;
+use advent::report::Format;
+use advent::input::InputSource;
+use advent::runner;
+use advent::scaffold;
+use advent::solver::Part;
+
+const USAGE: &str = "\
+Usage: advent [DAYS...] [--part <1|2>] [--input <PATH>] [--format <FORMAT>] [--check]
+       advent <DAY> --visualize [--part <1|2>] [--input <PATH>] [--delay <MS>] [--boundary <MODE>]
+       advent <DAY> --visualize --export <PATH> [--frames] [--generation <N>] [--scale <N>] [--part <1|2>] [--boundary <MODE>]
+       advent bench [DAYS...] [--part <1|2>] [--input <PATH>] [--runs <N>] [--warmup <N>] [--save <PATH>] [--baseline <PATH>]
+       advent new <DAY>
+       advent --list
+
+Arguments:
+  DAYS               A day (`20`) or an inclusive range of days (`10..24`), defaults to the latest day
+
+Options:
+  -p, --part <N>     Only solve the given part
+  -i, --input <P>    Read the input from the given file, or from stdin with `-` (a single day only)
+  -f, --format <F>   Output format: `text` (default), `json` (one object per line) or `csv`
+  -c, --check        Compare the answers with the expected ones, failing on mismatch
+      --answers <P>  Expected answers file used by `--check`, defaults to `answers.toml`
+  -l, --list         List the implemented days
+  -v, --visualize    Animate the day in the terminal instead of printing the answers (day 11 only)
+      --delay <MS>   Delay between the frames of `--visualize`, defaults to 100
+      --boundary <M> Edges of the grid with `--visualize`: `bounded` (default), `toroidal` or `reflective`
+
+Export options, writing images with `--visualize` instead of animating the terminal:
+      --export <P>   A `.png` or `.ppm` file, `-partN` being added to its name
+      --frames       Write every generation, `-partN-GGGG` being added to the names
+      --generation <N>
+                     Write generation N (or stop the frames there), defaults to the last one
+      --scale <N>    Width in pixels of a tile, defaults to 4
+  -h, --help         Print this help
+
+Benchmark options:
+  -n, --runs <N>     Number of timed runs, defaults to 10
+  -w, --warmup <N>   Number of untimed runs done first, defaults to 2
+      --save <P>     Save the statistics as a baseline in the given file (other days are kept)
+      --baseline <P> Compare the median timings with the given baseline
+
+`advent new` creates `src/dayN.rs` from `src/template.rs` and an empty `inputs/dayN.txt`,
+and registers the day in `src/lib.rs` and `src/days.rs`. Existing files are never overwritten.
+
+Inputs default to `inputs/dayNN.txt`, the directory can be overridden with $ADVENT_INPUT_DIR.
+Relative input directories are resolved from the crate root.";
+
+enum Selection {
+    Day(u8),
+    Range(RangeInclusive<u8>),
+}
+
+impl TryFrom<&str> for Selection {
+    type Error = String;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        let parse_day = |s: &str| s.parse::<u8>().map_err(|_| format!("invalid day `{}`", s));
+
+        if let Some((start, end)) = value.split_once("..") {
+            let end = end.strip_prefix('=').unwrap_or(end);
+            Ok(Selection::Range(parse_day(start)?..=parse_day(end)?))
+        } else {
+            Ok(Selection::Day(parse_day(value)?))
+        }
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Eq)]
+enum Command {
+    Solve,
+    Bench,
+    New,
+}
+
+struct Args {
+    command: Command,
+    runs: usize,
+    warmup: usize,
+    save: Option<PathBuf>,
+    baseline: Option<PathBuf>,
+
+    selections: Vec<Selection>,
+    part: Option<Part>,
+    input: InputSource,
+    format: Format,
+    answers: Option<PathBuf>,
+    visualize: bool,
+    delay: Duration,
+    boundary: Option<Boundary>,
+    export: Option<PathBuf>,
+    frames: bool,
+    generation: Option<usize>,
+    scale: usize,
+    list: bool,
+    help: bool,
+}
+
+fn parse_count(option: &str, value: Option<String>) -> Result<usize, String> {
+    let value = value.ok_or_else(|| format!("missing value for `{}`", option))?;
+    value.parse().map_err(|_| format!("invalid value `{}` for `{}`", value, option))
+}
+
+fn parse_args(args: impl Iterator<Item = String>) -> Result<Args, String> {
+    let mut args = args.peekable();
+    let command = match args.peek().map(String::as_str) {
+        Some("bench") => Command::Bench,
+        Some("new") => Command::New,
+        _ => Command::Solve,
+    };
+    if command != Command::Solve {
+        args.next();
+    }
+    let bench = command == Command::Bench;
+
+    let mut parsed = Args {
+        command,
+        runs: 10,
+        warmup: 2,
+        save: None,
+        baseline: None,
+
+        selections: vec![],
+        part: None,
+        input: InputSource::Default,
+        format: Format::Text,
+        answers: None,
+        visualize: false,
+        delay: Duration::from_millis(100),
+        boundary: None,
+        export: None,
+        frames: false,
+        generation: None,
+        scale: 4,
+        list: false,
+        help: false,
+    };
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-h" | "--help" => parsed.help = true,
+            "-l" | "--list" => parsed.list = true,
+            "-p" | "--part" => {
+                let value = args.next().ok_or("missing value for `--part`")?;
+                let part = Part::try_from(value.as_str()).map_err(|_| format!("invalid part `{}`, expected 1 or 2", value))?;
+                parsed.part = Some(part);
+            },
+            "-i" | "--input" => {
+                let value = args.next().ok_or("missing value for `--input`")?;
+                parsed.input = InputSource::from(value.as_str());
+            },
+            "-c" | "--check" => parsed.answers = parsed.answers.or_else(|| Some(answers::default_path())),
+            "--answers" => {
+                let value = args.next().ok_or("missing value for `--answers`")?;
+                parsed.answers = Some(PathBuf::from(value));
+            },
+            "-f" | "--format" => {
+                let value = args.next().ok_or("missing value for `--format`")?;
+                parsed.format = Format::try_from(value.as_str()).map_err(|_| format!("invalid format `{}`, expected text, json or csv", value))?;
+            },
+            "-v" | "--visualize" if command == Command::Solve => parsed.visualize = true,
+            "--delay" if command == Command::Solve => parsed.delay = Duration::from_millis(parse_count("--delay", args.next())? as u64),
+            "--boundary" if command == Command::Solve => {
+                let value = args.next().ok_or("missing value for `--boundary`")?;
+                let boundary = Boundary::try_from(value.as_str()).map_err(|_| format!("invalid boundary `{}`, expected bounded, toroidal or reflective", value))?;
+                parsed.boundary = Some(boundary);
+            },
+            "--export" if command == Command::Solve => {
+                let value = args.next().ok_or("missing value for `--export`")?;
+                parsed.export = Some(PathBuf::from(value));
+            },
+            "--frames" if command == Command::Solve => parsed.frames = true,
+            "--generation" if command == Command::Solve => parsed.generation = Some(parse_count("--generation", args.next())?),
+            "--scale" if command == Command::Solve => {
+                parsed.scale = parse_count("--scale", args.next())?;
+                if parsed.scale == 0 {
+                    return Err("`--scale` must be at least 1".to_string());
+                }
+            },
+            "-n" | "--runs" if bench => {
+                parsed.runs = parse_count("--runs", args.next())?;
+                if parsed.runs == 0 {
+                    return Err("`--runs` must be at least 1".to_string());
+                }
+            },
+            "-w" | "--warmup" if bench => parsed.warmup = parse_count("--warmup", args.next())?,
+            "--save" if bench => {
+                let value = args.next().ok_or("missing value for `--save`")?;
+                parsed.save = Some(PathBuf::from(value));
+            },
+            "--baseline" if bench => {
+                let value = args.next().ok_or("missing value for `--baseline`")?;
+                parsed.baseline = Some(PathBuf::from(value));
+            },
+            _ if arg.starts_with('-') => return Err(format!("unknown option `{}`", arg)),
+            _ => parsed.selections.push(Selection::try_from(arg.as_str())?),
+        }
+    }
+
+    Ok(parsed)
+}
+
+/// Resolves the selections into the list of days to run, in the requested order
+fn select_days(selections: &[Selection]) -> Result<Vec<u8>, String> {
+    if selections.is_empty() {
+        return Ok(vec![days::latest()]);
+    }
+
+    let mut selected = vec![];
+    for selection in selections {
+        match selection {
+            Selection::Day(day) => {
+                if !days::is_implemented(*day) {
+                    return Err(format!("day {} is not implemented (see `advent --list`)", day));
+                }
+                selected.push(*day);
+            },
+            Selection::Range(range) => {
+                let days_in_range: Vec<_> = DAYS.iter()
+                    .map(|(day, _)| *day)
+                    .filter(|day| range.contains(day))
+                    .collect();
+                if days_in_range.is_empty() {
+                    return Err(format!("no implemented day between {} and {} (see `advent --list`)", range.start(), range.end()));
+                }
+                selected.extend(days_in_range);
+            },
+        }
+    }
+
+    Ok(selected)
+}
+
+fn main() -> ExitCode {
+    let args = match parse_args(std::env::args().skip(1)) {
+        Ok(args) => args,
+        Err(message) => {
+            eprintln!("error: {}\n\n{}", message, USAGE);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    if args.help {
+        println!("{}", USAGE);
+        return ExitCode::SUCCESS;
+    }
+
+    if args.list {
+        println!("Implemented days: {}", DAYS.iter().map(|(day, _)| day.to_string()).collect::<Vec<_>>().join(", "));
+        return ExitCode::SUCCESS;
+    }
+
+    if args.command == Command::New {
+        return run_new(&args);
+    }
+
+    let selected = match select_days(&args.selections) {
+        Ok(selected) => selected,
+        Err(message) => {
+            eprintln!("error: {}", message);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    if args.input != InputSource::Default && selected.len() > 1 {
+        eprintln!("error: `--input` can only be used when solving a single day");
+        return ExitCode::FAILURE;
+    }
+
+    if args.command == Command::Bench {
+        return run_bench(&args, &selected);
+    }
+
+    if args.export.is_none() && (args.frames || args.generation.is_some()) {
+        eprintln!("error: `--frames` and `--generation` can only be used with `--export`");
+        return ExitCode::FAILURE;
+    }
+
+    if args.visualize {
+        return run_visualize(&args, &selected);
+    } else if args.boundary.is_some() || args.export.is_some() {
+        eprintln!("error: `--boundary` and `--export` can only be used with `--visualize`");
+        return ExitCode::FAILURE;
+    }
+
+    let answers = match args.answers.as_deref().map(Answers::load).transpose() {
+        Ok(answers) => answers,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    // A failing day is reported and the remaining days are still solved
+    let mut failed = false;
+    args.format.print_header();
+    for day in selected {
+        let mut result = runner::run(day, days::find(day).unwrap(), &args.input, args.part);
+        if let (Some(answers), Ok(result)) = (&answers, &mut result) {
+            answers.check(result);
+        }
+
+        failed |= result.as_ref().map_or(true, |result| result.has_failed_check());
+        args.format.print(day, &result);
+    }
+
+    if failed {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
+
+fn run_bench(args: &Args, selected: &[u8]) -> ExitCode {
+    let baseline = match args.baseline.as_deref().map(Baseline::load).transpose() {
+        Ok(baseline) => baseline,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let mut failed = false;
+    let mut results = vec![];
+    for &day in selected {
+        let result = args.input.read(day)
+            .map_err(|e| Error::from(e).in_day(day))
+            .and_then(|input| bench::bench(day, days::find(day).unwrap(), &input, args.part, args.warmup, args.runs));
+
+        match result {
+            Ok(result) => {
+                bench::print(&result, args.warmup, baseline.as_ref());
+                results.push(result);
+            },
+            Err(e) => {
+                eprintln!("error: {}\n", e);
+                failed = true;
+            }
+        }
+    }
+
+    if let Some(path) = &args.save {
+        let saved = Baseline::load_or_default(path).and_then(|mut saved| {
+            for result in &results {
+                saved.record(result);
+            }
+            saved.save(path)
+        });
+
+        match saved {
+            Ok(()) => println!("Baseline saved to {}", path.display()),
+            Err(e) => {
+                eprintln!("error: {}", e);
+                failed = true;
+            }
+        }
+    }
+
+    if failed {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
+
+fn run_visualize(args: &Args, selected: &[u8]) -> ExitCode {
+    let (day, visualizer) = match selected {
+        [day] => match days::find_visualizer(*day) {
+            Some(visualizer) => (*day, visualizer),
+            None => {
+                eprintln!("error: day {} cannot be visualized", day);
+                return ExitCode::FAILURE;
+            }
+        },
+        _ => {
+            eprintln!("error: `--visualize` expects a single day");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let result = args.input.read(day)
+        .map_err(Error::from)
+        .and_then(|input| visualizer(&input, &Visualization {
+            part: args.part,
+            delay: args.delay,
+            boundary: args.boundary.unwrap_or_default(),
+            export: args.export.clone().map(|path| Export {
+                path,
+                frames: args.frames,
+                generation: args.generation,
+                scale: args.scale,
+            }),
+        }))
+        .map_err(|e| e.in_day(day));
+
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run_new(args: &Args) -> ExitCode {
+    let day = match args.selections[..] {
+        [Selection::Day(day)] => day,
+        _ => {
+            eprintln!("error: `advent new` expects a single day\n\n{}", USAGE);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    match scaffold::new_day(Path::new(env!("CARGO_MANIFEST_DIR")), day) {
+        Ok(scaffold) => {
+            println!("Created {}", scaffold.module.display());
+            println!("Created {}", scaffold.input.display());
+            println!("Registered day {} in src/lib.rs and src/days.rs, run it with `cargo run -- {}`", day, day);
+            ExitCode::SUCCESS
+        },
+        Err(e) => {
+            eprintln!("error: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,124 @@
+use crate::error::{Error, Result};
+
+/// Returns `(g, x, y)` such that `g = gcd(a, b)` is non-negative and `a * x + b * y = g`
+pub fn extended_gcd(a: i64, b: i64) -> (i64, i64, i64) {
+    let (mut old_r, mut r) = (a, b);
+    let (mut old_x, mut x) = (1, 0);
+    let (mut old_y, mut y) = (0, 1);
+
+    while r != 0 {
+        let quotient = old_r / r;
+        (old_r, r) = (r, old_r - quotient * r);
+        (old_x, x) = (x, old_x - quotient * x);
+        (old_y, y) = (y, old_y - quotient * y);
+    }
+
+    if old_r < 0 {
+        (-old_r, -old_x, -old_y)
+    } else {
+        (old_r, old_x, old_y)
+    }
+}
+
+/// The inverse of `a` modulo `m`, in `0..m`, if `a` and `m` are coprime
+pub fn inv_mod(a: i64, m: i64) -> Option<i64> {
+    if m <= 0 {
+        return None;
+    }
+
+    match extended_gcd(a.rem_euclid(m), m) {
+        (1, x, _) => Some(x.rem_euclid(m)),
+        _ => None,
+    }
+}
+
+/// `base` to the power `exponent` modulo `m`, in `0..m`
+pub fn pow_mod(base: i64, mut exponent: u64, m: i64) -> i64 {
+    assert!(m > 0, "the modulus must be positive, got {}", m);
+
+    let m = m as i128;
+    let mut base = (base as i128).rem_euclid(m);
+    let mut result = 1 % m;
+    while exponent > 0 {
+        if exponent & 1 == 1 {
+            result = result * base % m;
+        }
+        base = base * base % m;
+        exponent >>= 1;
+    }
+
+    result as i64
+}
+
+/// Solves the system of congruences `x = a (mod n)` given as `(a, n)` pairs, the moduli not having to be coprime.
+/// Returns the smallest non-negative solution and the modulus of the solutions, the lcm of the moduli.
+pub fn crt(congruences: &[(i64, i64)]) -> Result<(i64, i64)> {
+    let (mut x, mut m) = (0i128, 1i64);
+
+    for &(a, n) in congruences {
+        if n <= 0 {
+            return Err(Error::new(format!("invalid modulus {}, moduli must be positive", n)));
+        }
+
+        // x + m * t = a (mod n) has a solution t if gcd(m, n) divides a - x
+        let (g, p, _) = extended_gcd(m, n);
+        let difference = a as i128 - x;
+        if difference % g as i128 != 0 {
+            return Err(Error::new(format!("x = {} (mod {}) contradicts the previous congruences", a, n)));
+        }
+
+        let lcm = m as i128 / g as i128 * n as i128;
+        let lcm = i64::try_from(lcm).map_err(|_| Error::new("the lcm of the moduli does not fit in 64 bits"))?;
+
+        let t = (difference / g as i128 * p as i128).rem_euclid((n / g) as i128);
+        x = (x + m as i128 * t).rem_euclid(lcm as i128);
+        m = lcm;
+    }
+
+    Ok((x as i64, m))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn gcd() {
+        for (a, b) in [(240, 46), (46, 240), (-12, 18), (17, 0), (0, 0), (7, -3)] {
+            let (g, x, y) = extended_gcd(a, b);
+            assert!(g >= 0);
+            assert_eq!(a * x + b * y, g, "{} {}", a, b);
+        }
+        assert_eq!(extended_gcd(240, 46).0, 2);
+        assert_eq!(extended_gcd(-12, 18).0, 6);
+    }
+
+    #[test]
+    fn inverse() {
+        assert_eq!(inv_mod(3, 11), Some(4));
+        assert_eq!(inv_mod(-3, 11), Some(7));
+        assert_eq!(inv_mod(10, 17), Some(12));
+        assert_eq!(inv_mod(2, 4), None);
+        assert_eq!(inv_mod(3, 0), None);
+    }
+
+    #[test]
+    fn power() {
+        assert_eq!(pow_mod(2, 10, 1000), 24);
+        assert_eq!(pow_mod(-2, 3, 5), 2);
+        assert_eq!(pow_mod(5, 0, 1), 0);
+        assert_eq!(pow_mod(123_456_789, 1_000_000_006, 1_000_000_007), 1);
+    }
+
+    #[test]
+    fn chinese_remainder() {
+        assert_eq!(crt(&[(2, 3), (3, 5), (2, 7)]).unwrap(), (23, 105));
+        assert_eq!(crt(&[(2, 4), (4, 6)]).unwrap(), (10, 12));
+        assert_eq!(crt(&[(-1, 7)]).unwrap(), (6, 7));
+        assert_eq!(crt(&[]).unwrap(), (0, 1));
+
+        assert!(crt(&[(1, 2), (2, 4)]).is_err());
+        assert!(crt(&[(1, 0)]).is_err());
+        assert!(crt(&[(1, 1_000_000_007), (2, 998_244_353), (3, 1_000_000_009)]).is_err());
+    }
+}
@@ -0,0 +1,259 @@
+use crate::answer::Answer;
+use crate::answers::Check;
+use crate::error::Result;
+use crate::runner::DayResult;
+use crate::solver::Part;
+
+/// How the results are written to the standard output
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Format {
+    /// Human readable summary of each day
+    Text,
+    /// One JSON object per line and per part
+    Json,
+    /// One CSV row per part, after a header row
+    Csv,
+}
+
+impl TryFrom<&str> for Format {
+    type Error = ();
+
+    fn try_from(value: &str) -> std::result::Result<Self, Self::Error> {
+        match value {
+            "text" => Ok(Format::Text),
+            "json" => Ok(Format::Json),
+            "csv" => Ok(Format::Csv),
+            _ => Err(()),
+        }
+    }
+}
+
+/// A flattened result, as written by the machine-readable formats
+struct Record<'a> {
+    day: u8,
+    part: Option<u8>,
+    answer: Option<&'a Answer>,
+    duration_ns: Option<u128>,
+    status: &'static str,
+    expected: Option<&'a Answer>,
+    error: Option<String>,
+}
+
+fn part_number(part: Part) -> u8 {
+    match part {
+        Part::One => 1,
+        Part::Two => 2,
+    }
+}
+
+fn records(day: u8, result: &Result<DayResult>) -> Vec<Record<'_>> {
+    match result {
+        Ok(result) => result.parts.iter().map(|part| Record {
+            day: result.day,
+            part: Some(part_number(part.part)),
+            answer: Some(&part.answer),
+            duration_ns: Some(part.duration.as_nanos()),
+            status: match part.check {
+                None => "ok",
+                Some(Check::Pass) => "pass",
+                Some(Check::Fail { .. }) => "fail",
+                Some(Check::Missing) => "missing",
+            },
+            expected: match &part.check {
+                Some(Check::Pass) => Some(&part.answer),
+                Some(Check::Fail { expected }) => Some(expected),
+                _ => None,
+            },
+            error: None,
+        }).collect(),
+        Err(e) => vec![Record {
+            day,
+            part: None,
+            answer: None,
+            duration_ns: None,
+            status: "error",
+            expected: None,
+            error: Some(e.to_string()),
+        }],
+    }
+}
+
+impl Format {
+    /// Prints what comes before the results, if anything
+    pub fn print_header(&self) {
+        if *self == Format::Csv {
+            println!("day,part,answer,duration_ns,status,expected,error");
+        }
+    }
+
+    /// Prints the result of the given day, errors going to the standard error in text mode
+    pub fn print(&self, day: u8, result: &Result<DayResult>) {
+        match self {
+            Format::Text => match result {
+                Ok(result) => print_text(result),
+                Err(e) => eprintln!("error: {}\n", e),
+            },
+            Format::Json => {
+                for record in records(day, result) {
+                    println!("{}", to_json(&record));
+                }
+            },
+            Format::Csv => {
+                for record in records(day, result) {
+                    println!("{}", to_csv(&record));
+                }
+            },
+        }
+    }
+}
+
+fn print_text(result: &DayResult) {
+    println!("========= DAY {} ========", result.day);
+    for part in &result.parts {
+        let check = match &part.check {
+            None => String::new(),
+            Some(Check::Pass) => " [pass]".to_string(),
+            Some(Check::Fail { expected }) => format!(" [FAIL, expected {}]", expected),
+            Some(Check::Missing) => " [no expected answer]".to_string(),
+        };
+        if part.answer.is_multiline() {
+            println!("Solving part {}: (took {:?}){}\n{}", part_number(part.part), part.duration, check, part.answer);
+        } else {
+            println!("Solving part {}: {} (took {:?}){}", part_number(part.part), part.answer, part.duration, check);
+        }
+    }
+    println!();
+}
+
+fn json_string(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len() + 2);
+    escaped.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c if c.is_control() => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+    escaped.push('"');
+    escaped
+}
+
+fn to_json(record: &Record) -> String {
+    let null = || "null".to_string();
+    format!(
+        "{{\"day\":{},\"part\":{},\"answer\":{},\"duration_ns\":{},\"status\":{},\"expected\":{},\"error\":{}}}",
+        record.day,
+        record.part.map_or_else(null, |part| part.to_string()),
+        record.answer.map_or_else(null, |answer| json_string(&answer.to_string())),
+        record.duration_ns.map_or_else(null, |duration| duration.to_string()),
+        json_string(record.status),
+        record.expected.map_or_else(null, |expected| json_string(&expected.to_string())),
+        record.error.as_deref().map_or_else(null, json_string),
+    )
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+fn to_csv(record: &Record) -> String {
+    [
+        record.day.to_string(),
+        record.part.map(|part| part.to_string()).unwrap_or_default(),
+        record.answer.map(|answer| csv_field(&answer.to_string())).unwrap_or_default(),
+        record.duration_ns.map(|duration| duration.to_string()).unwrap_or_default(),
+        record.status.to_string(),
+        record.expected.map(|expected| csv_field(&expected.to_string())).unwrap_or_default(),
+        record.error.as_deref().map(csv_field).unwrap_or_default(),
+    ].join(",")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::error::Error;
+    use crate::runner::PartResult;
+
+    fn day_result() -> Result<DayResult> {
+        Ok(DayResult {
+            day: 20,
+            parse_duration: Duration::from_nanos(5),
+            parts: vec![
+                PartResult { part: Part::One, answer: Answer::from(42), duration: Duration::from_nanos(7), check: Some(Check::Fail { expected: Answer::from(41) }) },
+                PartResult { part: Part::Two, answer: Answer::from("#.\n.#\n"), duration: Duration::from_nanos(9), check: None },
+            ],
+        })
+    }
+
+    #[test]
+    fn json_escaping() {
+        assert_eq!(json_string("plain"), "\"plain\"");
+        assert_eq!(json_string("say \"hi\""), "\"say \\\"hi\\\"\"");
+        assert_eq!(json_string("a\\b"), "\"a\\\\b\"");
+        assert_eq!(json_string("a\nb\r\tc"), "\"a\\nb\\r\\tc\"");
+        assert_eq!(json_string("\u{1}\u{7f}"), "\"\\u0001\\u007f\"");
+        assert_eq!(json_string("a,b"), "\"a,b\"");
+    }
+
+    #[test]
+    fn csv_quoting() {
+        assert_eq!(csv_field("plain"), "plain");
+        assert_eq!(csv_field("a\\b"), "a\\b");
+        assert_eq!(csv_field("a,b"), "\"a,b\"");
+        assert_eq!(csv_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+        assert_eq!(csv_field("a\nb"), "\"a\nb\"");
+        assert_eq!(csv_field("a\rb"), "\"a\rb\"");
+    }
+
+    #[test]
+    fn flattened_results() {
+        let result = day_result();
+        let records = records(20, &result);
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].part, Some(1));
+        assert_eq!(records[0].status, "fail");
+        assert_eq!(records[0].expected, Some(&Answer::from(41)));
+        assert_eq!(records[1].part, Some(2));
+        assert_eq!(records[1].status, "ok");
+        assert_eq!(records[1].expected, None);
+
+        assert_eq!(
+            to_json(&records[0]),
+            "{\"day\":20,\"part\":1,\"answer\":\"42\",\"duration_ns\":7,\"status\":\"fail\",\"expected\":\"41\",\"error\":null}",
+        );
+        assert_eq!(
+            to_json(&records[1]),
+            "{\"day\":20,\"part\":2,\"answer\":\"#.\\n.#\",\"duration_ns\":9,\"status\":\"ok\",\"expected\":null,\"error\":null}",
+        );
+        assert_eq!(to_csv(&records[0]), "20,1,42,7,fail,41,");
+        assert_eq!(to_csv(&records[1]), "20,2,\"#.\n.#\",9,ok,,");
+    }
+
+    #[test]
+    fn error_records() {
+        let result = Err(Error::new("bad \"rating\", line 3").in_day(10));
+        let records = records(10, &result);
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].part, None);
+        assert_eq!(records[0].status, "error");
+
+        let message = result.as_ref().err().unwrap().to_string();
+        assert_eq!(
+            to_json(&records[0]),
+            format!("{{\"day\":10,\"part\":null,\"answer\":null,\"duration_ns\":null,\"status\":\"error\",\"expected\":null,\"error\":{}}}", json_string(&message)),
+        );
+        assert_eq!(to_csv(&records[0]), format!("10,,,,error,,{}", csv_field(&message)));
+        assert!(to_csv(&records[0]).ends_with("\"\"rating\"\", line 3\""));
+    }
+}
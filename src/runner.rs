@@ -0,0 +1,126 @@
+use std::any::Any;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::mpsc;
+use std::time::{Duration, Instant};
+
+use threadpool::ThreadPool;
+
+use crate::answer::Answer;
+use crate::answers::Check;
+use crate::error::{Error, Result};
+use crate::input::InputSource;
+use crate::days::Runner;
+use crate::solver::{Part, Solver};
+
+pub struct PartResult {
+    pub part: Part,
+    pub answer: Answer,
+    pub duration: Duration,
+    /// Comparison with the expected answer, when checked
+    pub check: Option<Check>,
+}
+
+pub struct DayResult {
+    pub day: u8,
+    pub parse_duration: Duration,
+    pub parts: Vec<PartResult>,
+}
+
+impl DayResult {
+    /// Time spent parsing the input and solving the parts
+    pub fn total_duration(&self) -> Duration {
+        self.parse_duration + self.parts.iter().map(|part| part.duration).sum::<Duration>()
+    }
+
+    /// Whether one of the answers differs from the expected one
+    pub fn has_failed_check(&self) -> bool {
+        self.parts.iter().any(|part| matches!(part.check, Some(Check::Fail { .. })))
+    }
+}
+
+/// Solves and times the requested part (or both parts) of the given day.
+pub fn solve<S: Solver>(day: u8, input: &str, part: Option<Part>) -> Result<DayResult> {
+    let start = Instant::now();
+    let solution = S::parse(input).map_err(|e| e.in_day(day))?;
+    let parse_duration = start.elapsed();
+
+    let mut parts = vec![];
+    if part != Some(Part::Two) {
+        let start = Instant::now();
+        let answer = solution.part1().map_err(|e| e.in_day(day))?;
+        let duration = start.elapsed();
+        parts.push(PartResult { part: Part::One, answer: answer.into(), duration, check: None });
+    }
+
+    if part != Some(Part::One) {
+        let start = Instant::now();
+        let answer = solution.part2().map_err(|e| e.in_day(day))?;
+        let duration = start.elapsed();
+        parts.push(PartResult { part: Part::Two, answer: answer.into(), duration, check: None });
+    }
+
+    Ok(DayResult {
+        day,
+        parse_duration,
+        parts,
+    })
+}
+
+/// Reads the input of the given day and solves it. A panic of the day becomes an error,
+/// so that the other days can still be solved.
+pub fn run(day: u8, runner: Runner, input: &InputSource, part: Option<Part>) -> Result<DayResult> {
+    let input = input.read(day).map_err(|e| Error::from(e).in_day(day))?;
+    panic::catch_unwind(AssertUnwindSafe(|| runner(day, &input, part)))
+        .unwrap_or_else(|payload| Err(Error::new(format!("panicked: {}", panic_message(&*payload))).in_day(day)))
+}
+
+/// Runs the given days on a pool of `jobs` threads, returning the results in the order of `days`
+pub fn run_parallel(days: &[(u8, Runner)], jobs: usize, part: Option<Part>) -> Vec<(u8, Result<DayResult>)> {
+    let pool = ThreadPool::new(jobs);
+    let (tx, rx) = mpsc::channel();
+
+    for (i, (day, runner)) in days.iter().copied().enumerate() {
+        let tx = tx.clone();
+        pool.execute(move || {
+            tx.send((i, day, run(day, runner, &InputSource::Default, part))).unwrap();
+        });
+    }
+    drop(tx);
+
+    let mut results: Vec<_> = rx.iter().collect();
+    results.sort_by_key(|(i, _, _)| *i);
+    results.into_iter().map(|(_, day, result)| (day, result)).collect()
+}
+
+fn panic_message(payload: &(dyn Any + Send)) -> &str {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message
+    } else {
+        "unknown cause"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn panicking(_day: u8, _input: &str, _part: Option<Part>) -> Result<DayResult> {
+        panic!("the seating never settles");
+    }
+
+    #[test]
+    fn panics_become_errors() {
+        let results = run_parallel(&[(10, panicking), (11, panicking)], 2, None);
+        let days: Vec<_> = results.iter().map(|(day, _)| *day).collect();
+        assert_eq!(days, [10, 11]);
+
+        let error = results[0].1.as_ref().err().unwrap();
+        assert_eq!(error.day, Some(10));
+        assert_eq!(error.message, "panicked: the seating never settles");
+
+        let error = run(11, panicking, &InputSource::Default, None).err().unwrap();
+        assert_eq!(error.day, Some(11));
+    }
+}
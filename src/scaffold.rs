@@ -0,0 +1,139 @@
+use std::fs::{read_to_string, write, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use regex::Regex;
+
+use crate::error::{Error, Result};
+
+/// Paths of the files created when scaffolding a day
+pub struct Scaffold {
+    pub module: PathBuf,
+    pub input: PathBuf,
+}
+
+/// Creates `src/dayN.rs` from `src/template.rs` and an empty `inputs/dayN.txt` in the crate at `root`,
+/// then registers the new module in `src/lib.rs` and in the day registry (`src/days.rs`).
+/// Nothing is written if one of the files already exists or the day is already registered.
+pub fn new_day(root: &Path, day: u8) -> Result<Scaffold> {
+    if !(1..=25).contains(&day) {
+        return Err(Error::new(format!("invalid day {}, expected a day between 1 and 25", day)));
+    }
+
+    let module = root.join("src").join(format!("day{}.rs", day));
+    let input = root.join("inputs").join(format!("day{}.txt", day));
+    let lib_path = root.join("src").join("lib.rs");
+    let days_path = root.join("src").join("days.rs");
+
+    for path in [&module, &input] {
+        if path.exists() {
+            return Err(Error::new(format!("{} already exists, refusing to overwrite it", path.display())));
+        }
+    }
+
+    // Prepare every change first so that a failure leaves the crate untouched
+    let template = read(&root.join("src").join("template.rs"))?;
+    let lib = register_module(&read(&lib_path)?, day)?;
+    let days = register_day(&read(&days_path)?, day)?;
+
+    // `create_new` guards against the files appearing in the meantime
+    let mut module_file = OpenOptions::new().write(true).create_new(true).open(&module).map_err(|e| io_error(&module, e))?;
+    module_file.write_all(template.as_bytes()).map_err(|e| io_error(&module, e))?;
+    OpenOptions::new().write(true).create_new(true).open(&input).map_err(|e| io_error(&input, e))?;
+    write(&lib_path, lib).map_err(|e| io_error(&lib_path, e))?;
+    write(&days_path, days).map_err(|e| io_error(&days_path, e))?;
+
+    Ok(Scaffold { module, input })
+}
+
+fn read(path: &Path) -> Result<String> {
+    read_to_string(path).map_err(|e| io_error(path, e))
+}
+
+fn io_error(path: &Path, e: std::io::Error) -> Error {
+    Error::new(format!("{}: {}", path.display(), e))
+}
+
+/// Adds `pub mod dayN;` to `lib.rs`, keeping the day modules sorted
+pub fn register_module(lib: &str, day: u8) -> Result<String> {
+    let pattern = Regex::new(r"^pub mod day(\d+);$").unwrap();
+    insert_sorted(lib, &pattern, day, format!("pub mod day{};", day))
+        .ok_or_else(|| Error::new(format!("could not register day {} in lib.rs", day)))
+}
+
+/// Adds the `use crate::dayN;` import and the `(N, solve::<dayN::Solution>)` entry to the day registry
+pub fn register_day(days: &str, day: u8) -> Result<String> {
+    let import = Regex::new(r"^use crate::day(\d+);$").unwrap();
+    let entry = Regex::new(r"^\s+\((\d+), solve::<day\d+::Solution>\),$").unwrap();
+
+    insert_sorted(days, &import, day, format!("use crate::day{};", day))
+        .and_then(|days| insert_sorted(&days, &entry, day, format!("    ({}, solve::<day{}::Solution>),", day, day)))
+        .ok_or_else(|| Error::new(format!("could not register day {} in days.rs", day)))
+}
+
+/// Inserts `line` among the consecutive lines matching `pattern`, whose first capture is the day.
+/// Returns `None` if there is no such line, or if the day is already present.
+fn insert_sorted(content: &str, pattern: &Regex, day: u8, line: String) -> Option<String> {
+    let mut lines: Vec<_> = content.lines().map(str::to_string).collect();
+
+    let matching: Vec<(usize, u8)> = lines.iter()
+        .enumerate()
+        .filter_map(|(i, line)| pattern.captures(line).and_then(|c| c[1].parse().ok()).map(|d| (i, d)))
+        .collect();
+
+    if matching.is_empty() || matching.iter().any(|(_, d)| *d == day) {
+        return None;
+    }
+
+    let position = matching.iter()
+        .find(|(_, d)| *d > day)
+        .map_or(matching.last().unwrap().0 + 1, |(i, _)| *i);
+    lines.insert(position, line);
+
+    let mut result = lines.join("\n");
+    if content.ends_with('\n') {
+        result.push('\n');
+    }
+    Some(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn registers_module_in_order() {
+        let lib = "pub mod days;\n\npub mod day10;\npub mod day13;\n";
+
+        assert_eq!(register_module(lib, 11).unwrap(), "pub mod days;\n\npub mod day10;\npub mod day11;\npub mod day13;\n");
+        assert_eq!(register_module(lib, 25).unwrap(), "pub mod days;\n\npub mod day10;\npub mod day13;\npub mod day25;\n");
+        assert_eq!(register_module(lib, 1).unwrap(), "pub mod days;\n\npub mod day1;\npub mod day10;\npub mod day13;\n");
+        assert!(register_module(lib, 13).is_err());
+    }
+
+    #[test]
+    fn registers_day_in_order() {
+        let days = "\
+use crate::day10;
+use crate::day13;
+
+pub const DAYS: &[(u8, Runner)] = &[
+    (10, solve::<day10::Solution>),
+    (13, solve::<day13::Solution>),
+];
+";
+
+        assert_eq!(register_day(days, 12).unwrap(), "\
+use crate::day10;
+use crate::day12;
+use crate::day13;
+
+pub const DAYS: &[(u8, Runner)] = &[
+    (10, solve::<day10::Solution>),
+    (12, solve::<day12::Solution>),
+    (13, solve::<day13::Solution>),
+];
+");
+        assert!(register_day(days, 10).is_err());
+    }
+}
@@ -0,0 +1,37 @@
+use crate::answer::Answer;
+use crate::error::Result;
+
+/// Common interface implemented by every `dayNN::Solution`.
+/// The parsed input is never modified, so the parts can be run in any order, any number of times.
+pub trait Solver: Sized {
+    /// Types of the answers, converted to an `Answer` when reported
+    type Answer1: Into<Answer>;
+    type Answer2: Into<Answer>;
+
+    /// Builds the solution from the raw puzzle input, failing on malformed input.
+    fn parse(input: &str) -> Result<Self>;
+
+    /// Solves the first part, failing when the input, although well-formed, has no answer.
+    fn part1(&self) -> Result<Self::Answer1>;
+
+    /// Solves the second part, failing when the input, although well-formed, has no answer.
+    fn part2(&self) -> Result<Self::Answer2>;
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Part {
+    One,
+    Two,
+}
+
+impl TryFrom<&str> for Part {
+    type Error = ();
+
+    fn try_from(value: &str) -> std::result::Result<Self, Self::Error> {
+        match value {
+            "1" => Ok(Part::One),
+            "2" => Ok(Part::Two),
+            _ => Err(()),
+        }
+    }
+}
@@ -1,49 +1,34 @@
-use std::fs::read_to_string;
-use std::io::{self, Write};
-
 #[allow(unused_imports)]
 use itertools::Itertools;
 
+use crate::error::Result;
+use crate::input;
+use crate::solver::Solver;
+
 pub struct Solution {
     lines: Vec<String>,
 }
 
-impl Solution {
-    pub fn init() -> Self {
+impl Solver for Solution {
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(input: &str) -> Result<Self> {
         let mut lines = Vec::new();
-        for line in read_to_string("inputs/dayREPLACEDAY.txt").unwrap().lines() {
+        for line in input::lines(input) {
             lines.push(line.to_string());
         }
 
-        Self {
+        Ok(Self {
             lines,
-        }
+        })
     }
 
-    fn part1(&mut self) {
-
+    fn part1(&self) -> Result<usize> {
+        Ok(0)
     }
 
-    fn part2(&mut self) {
-
+    fn part2(&self) -> Result<usize> {
+        Ok(0)
     }
-
-    pub fn solve(&mut self) {
-        println!("========= DAY REPLACEDAY ========");
-        print!("Solving part 1: ");
-        io::stdout().flush().unwrap();
-
-        let start = std::time::Instant::now();
-        let part1 = self.part1();
-        let part1_time = start.elapsed();
-        println!("{:?} (took {:?})", part1, part1_time);
-
-        print!("Solving part 2: ");
-        io::stdout().flush().unwrap();
-        let start = std::time::Instant::now();
-        let part2 = self.part2();
-        let part2_time = start.elapsed();
-        println!("{:?} (took {:?})", part2, part2_time);
-        println!();
-    }
-}
\ No newline at end of file
+}